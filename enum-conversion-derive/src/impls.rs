@@ -1,13 +1,17 @@
+use std::collections::HashSet;
+
 use super::*;
-use crate::parse_attributes::{ErrorConfig, VariantInfo};
-use crate::parse_enum::ImplGenerics;
+use crate::parse_attributes::{negate_cfg, ErrorConfig, VariantInfo, Wrapper};
+use crate::parse_enum::{
+    get_bound_alias, get_kind, get_view, pretty_type, to_snake_case, ImplGenerics,
+};
 
 /// Implement the helper trait `GetVariant`.
 pub(crate) fn impl_get_variant(
     name: &str,
     fullname: &str,
     impl_generics: &ImplGenerics,
-    field_map: &HashMap<String, VariantInfo>,
+    variants: &[VariantInfo],
     templater: &Tera,
 ) -> String {
     let ImplGenerics {
@@ -16,8 +20,15 @@ pub(crate) fn impl_get_variant(
         ..
     } = impl_generics;
     let mut impl_string = String::new();
-    for (field, VariantInfo { ty, .. }) in field_map.iter() {
+    for VariantInfo {
+        name: field,
+        ty,
+        attrs,
+        ..
+    } in variants.iter()
+    {
         let mut context = Context::new();
+        context.insert("Attrs", attrs);
         context.insert("generics", impl_generics);
         context.insert("Type", ty);
         context.insert("Marker", &get_marker(name, field));
@@ -34,6 +45,67 @@ pub(crate) fn impl_get_variant(
     impl_string
 }
 
+/// Implement the `ContainsVariant` trait, which names the
+/// marker of the variant holding each type.
+pub(crate) fn impl_contains_variant(
+    name: &str,
+    fullname: &str,
+    impl_generics: &ImplGenerics,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let mut impl_string = String::new();
+    for VariantInfo {
+        name: field,
+        ty,
+        attrs,
+        ..
+    } in variants.iter()
+    {
+        let mut context = Context::new();
+        context.insert("Attrs", attrs);
+        context.insert("generics", impl_generics);
+        context.insert("Type", ty);
+        context.insert("Marker", &get_marker(name, field));
+        context.insert("fullname", fullname);
+        context.insert("Where", where_clause);
+        impl_string.push_str(
+            &templater
+                .render("contains_variant", &context)
+                .expect("Failed to render the ContainsVariant template"),
+        );
+    }
+    impl_string
+}
+
+/// Adds the bound that the enum implements `GetVariant` for
+/// the variant's type and marker to the where clause.
+fn with_marker_bound(where_clause: &str, name: &str, fullname: &str, info: &VariantInfo) -> String {
+    let mut where_string = where_clause.to_string();
+    let marker_bound = if where_clause.is_empty() {
+        format!(
+            "where\n {}: GetVariant<{}, {}>",
+            fullname,
+            &info.ty,
+            get_marker(name, &info.name)
+        )
+    } else {
+        format!(
+            ",\n {}: GetVariant<{}, {}>",
+            fullname,
+            &info.ty,
+            get_marker(name, &info.name)
+        )
+    };
+    where_string.push_str(&marker_bound);
+    where_string
+}
+
 /// Implement the `TryFrom` traits for each type in the
 /// enum. Uses the `GetVariant` helper trait and marker structs
 /// to avoid generic parameter ambiguity and restrictions
@@ -43,7 +115,7 @@ pub(crate) fn impl_try_from(
     fullname: &str,
     impl_generics: &ImplGenerics,
     error_config: &ErrorConfig,
-    field_map: &HashMap<String, VariantInfo>,
+    variants: &[VariantInfo],
     templater: &Tera,
 ) -> String {
     let ImplGenerics {
@@ -53,29 +125,14 @@ pub(crate) fn impl_try_from(
     } = impl_generics;
     let mut impl_string = String::new();
     let (error, _) = error_config.to_template();
-    for (field, info) in field_map.iter() {
+    for info in variants.iter() {
         if !info.try_from {
             continue;
         };
-        let mut where_string = where_clause.to_string();
-        let marker_bound = if where_clause.is_empty() {
-            format!(
-                "where\n {}: GetVariant<{}, {}>",
-                fullname,
-                &info.ty,
-                get_marker(name, field)
-            )
-        } else {
-            format!(
-                ",\n {}: GetVariant<{}, {}>",
-                fullname,
-                &info.ty,
-                get_marker(name, field)
-            )
-        };
-        where_string.push_str(&marker_bound);
+        let where_string = with_marker_bound(where_clause, name, fullname, info);
 
         let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
         context.insert("generics", impl_generics);
         context.insert("generics_ref", impl_generics_ref);
         context.insert("Lifetime", ENUM_CONV_LIFETIME);
@@ -97,7 +154,7 @@ pub(crate) fn impl_try_to(
     fullname: &str,
     impl_generics: &ImplGenerics,
     error_config: &ErrorConfig,
-    field_map: &HashMap<String, VariantInfo>,
+    variants: &[VariantInfo],
     templater: &Tera,
 ) -> String {
     let ImplGenerics {
@@ -107,26 +164,11 @@ pub(crate) fn impl_try_to(
     } = impl_generics;
     let mut impl_string = String::new();
     let (error, map) = error_config.to_template();
-    for (field, info) in field_map.iter() {
-        let mut where_string = where_clause.to_string();
-        let marker_bound = if where_clause.is_empty() {
-            format!(
-                "where\n {}: GetVariant<{}, {}>",
-                fullname,
-                &info.ty,
-                get_marker(name, field)
-            )
-        } else {
-            format!(
-                ",\n {}: GetVariant<{}, {}>",
-                fullname,
-                &info.ty,
-                get_marker(name, field)
-            )
-        };
-        where_string.push_str(&marker_bound);
+    for info in variants.iter() {
+        let where_string = with_marker_bound(where_clause, name, fullname, info);
 
         let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
         context.insert("generics", impl_generics);
         context.insert("generics_ref", impl_generics_ref);
         context.insert("Type", &info.ty);
@@ -147,7 +189,7 @@ pub(crate) fn impl_try_to(
 pub(crate) fn impl_from(
     fullname: &str,
     impl_generics: &ImplGenerics,
-    field_map: &HashMap<String, VariantInfo>,
+    variants: &[VariantInfo],
     templater: &Tera,
 ) -> String {
     let ImplGenerics {
@@ -156,8 +198,15 @@ pub(crate) fn impl_from(
         ..
     } = impl_generics;
     let mut impl_string = String::new();
-    for (field, VariantInfo { ty, .. }) in field_map.iter() {
+    for VariantInfo {
+        name: field,
+        ty,
+        attrs,
+        ..
+    } in variants.iter()
+    {
         let mut context = Context::new();
+        context.insert("Attrs", attrs);
         context.insert("generics", impl_generics);
         context.insert("Type", ty);
         context.insert("fullname", fullname);
@@ -172,6 +221,868 @@ pub(crate) fn impl_from(
     impl_string.parse().unwrap()
 }
 
+/// Implement the `Variants` trait describing the variants of
+/// the enum. Unlike the other traits, this is implemented once
+/// for the whole enum, so variants with `#[cfg]` attributes
+/// are handled inside the impl.
+pub(crate) fn impl_variants(
+    name: &str,
+    fullname: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let mut names = String::new();
+    let mut type_names = String::new();
+    let mut arms = String::new();
+    let mut marker_impls = String::new();
+    for (ix, info) in variants.iter().enumerate() {
+        names.push_str(&format!("{}{:?}, ", info.cfg, info.name));
+        type_names.push_str(&format!("{}{:?}, ", info.cfg, pretty_type(&info.ty)));
+        // If an earlier variant may be configured away, the
+        // index has to be counted when the crate is compiled.
+        let index = if variants[..ix].iter().all(|prev| prev.cfg.is_empty()) {
+            ix.to_string()
+        } else {
+            let mut index = String::from("{ let mut index = 0; ");
+            for prev in variants[..ix].iter() {
+                index.push_str(&format!("{}{{ index += 1; }} ", prev.cfg));
+            }
+            index.push_str("index }");
+            index
+        };
+        arms.push_str(&format!(
+            "            {}{}::{}(_) => {},\n",
+            info.cfg, name, info.name, index
+        ));
+
+        let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
+        context.insert("Marker", &get_marker(name, &info.name));
+        context.insert("Index", &index);
+        marker_impls.push_str(
+            &templater
+                .render("marker_index", &context)
+                .expect("Failed to render the MarkerIndex template"),
+        );
+    }
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("generics", impl_generics);
+    context.insert("fullname", fullname);
+    context.insert("Where", where_clause);
+    context.insert("Names", &names);
+    context.insert("TypeNames", &type_names);
+    context.insert("Arms", &arms);
+    let impl_string = templater
+        .render("variants", &context)
+        .expect("Failed to render the Variants template");
+    format!("{}{}", impl_string, marker_impls)
+}
+
+/// Implement the fieldless `Kind` enum requested by `#[DeriveKind]`,
+/// along with the `kind` methods on the enum and the `MarkerKind`
+/// trait on its markers.
+pub(crate) fn impl_kind(
+    name: &str,
+    fullname: &str,
+    vis: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let kind = get_kind(name);
+    let mut kind_variants = String::new();
+    let mut all = String::new();
+    let mut name_arms = String::new();
+    let mut from_str_arms = String::new();
+    let mut kind_arms = String::new();
+    let mut marker_impls = String::new();
+    for info in variants.iter() {
+        let VariantInfo {
+            name: field,
+            cfg: var_cfg,
+            ..
+        } = info;
+        kind_variants.push_str(&format!("    {}{},\n", var_cfg, field));
+        all.push_str(&format!("{}{}::{}, ", var_cfg, kind, field));
+        name_arms.push_str(&format!(
+            "            {}{}::{} => {:?},\n",
+            var_cfg, kind, field, field
+        ));
+        from_str_arms.push_str(&format!(
+            "            {}{:?} => Ok({}::{}),\n",
+            var_cfg, field, kind, field
+        ));
+        kind_arms.push_str(&format!(
+            "            {}{}::{}(_) => {}::{},\n",
+            var_cfg, name, field, kind, field
+        ));
+
+        let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
+        context.insert("Kind", &kind);
+        context.insert("Marker", &get_marker(name, field));
+        context.insert("field", field);
+        marker_impls.push_str(
+            &templater
+                .render("marker_kind", &context)
+                .expect("Failed to render the MarkerKind template"),
+        );
+    }
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("vis", vis);
+    context.insert("Kind", &kind);
+    context.insert("Param", ENUM_CONV_TYPE_PARAM);
+    context.insert("generics", impl_generics);
+    context.insert("fullname", fullname);
+    context.insert("Where", where_clause);
+    context.insert("Variants", &kind_variants);
+    context.insert("All", &all);
+    context.insert("NameArms", &name_arms);
+    context.insert("FromStrArms", &from_str_arms);
+    context.insert("KindArms", &kind_arms);
+    let mut impl_string = templater
+        .render("kind", &context)
+        .expect("Failed to render the Kind template");
+    impl_string.push_str(&marker_impls);
+    impl_string
+}
+
+/// Adds a lifetime as the first parameter to a list of generic
+/// parameters or arguments, e.g. `<T>` becomes `<'enum_conv, T>`.
+fn prepend_lifetime(generics: &str, lifetime: &str) -> String {
+    match generics.strip_prefix('<') {
+        Some(rest) => format!("<{}, {}", lifetime, rest),
+        None => format!("<{}>", lifetime),
+    }
+}
+
+/// Implement the set of variants requested by `#[DeriveVariantSet]`,
+/// holding a bit for each variant. Variants are added by kind or by
+/// the type they hold, so the kind must also be generated.
+pub(crate) fn impl_set(
+    name: &str,
+    fullname: &str,
+    vis: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let contains = format!(
+        "<{} as enum_conversion_traits::ContainsVariant<{}>>",
+        fullname, ENUM_CONV_TYPE_PARAM
+    );
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("name", name);
+    context.insert("vis", vis);
+    context.insert("Set", &format!("{}Set", name));
+    context.insert("Kind", &get_kind(name));
+    context.insert("generics", impl_generics);
+    context.insert("args", fullname.strip_prefix(name).unwrap_or_default());
+    context.insert("Where", where_clause);
+    context.insert("fullname", fullname);
+    context.insert("Param", ENUM_CONV_TYPE_PARAM);
+    // Variants removed by `#[cfg]` are counted, which at worst
+    // leaves some bits unused.
+    context.insert("Words", &variants.len().div_ceil(64).max(1));
+    context.insert(
+        "Bound",
+        &format!(
+            "        {}: enum_conversion_traits::ContainsVariant<{}>,\n        \
+             {}::Marker: enum_conversion_traits::MarkerIndex,\n",
+            fullname, ENUM_CONV_TYPE_PARAM, contains
+        ),
+    );
+    context.insert(
+        "Index",
+        &format!(
+            "<{}::Marker as enum_conversion_traits::MarkerIndex>::INDEX",
+            contains
+        ),
+    );
+    templater
+        .render("set", &context)
+        .expect("Failed to render the set template")
+}
+
+/// Implement the visitor traits requested by `#[DeriveVisitor]`.
+/// There is one trait for visiting the variants by value, one
+/// by reference and one by mutable reference. Each has a method
+/// `visit_<variant>` per variant, so adding a variant breaks
+/// every visitor that does not handle it.
+pub(crate) fn impl_visitor(
+    name: &str,
+    fullname: &str,
+    vis: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let methods = variants
+        .iter()
+        .map(|info| (info, format!("visit_{}", to_snake_case(&info.name))))
+        .collect::<Vec<(&VariantInfo, String)>>();
+    let unique = methods
+        .iter()
+        .map(|(_, method)| method)
+        .collect::<HashSet<&String>>();
+    if unique.len() != methods.len() {
+        panic!(
+            "Cannot derive a visitor for enum `{}` as the names of its variants \
+             do not give distinct method names.",
+            name
+        );
+    }
+    // The visitors by reference take the lifetime of the
+    // reference as their first generic parameter.
+    let args = fullname.strip_prefix(name).unwrap_or_default();
+    let args_ref = prepend_lifetime(args, ENUM_CONV_LIFETIME);
+    let generics_ref = prepend_lifetime(impl_generics, ENUM_CONV_LIFETIME);
+
+    let visitor = format!("{}Visitor", name);
+    let ref_visitor = format!("{}RefVisitor", name);
+    let mut_visitor = format!("{}MutVisitor", name);
+    let mut impl_string = String::new();
+    for (trait_name, mode, generics, prefix) in [
+        (&visitor, "by value", impl_generics, "".to_string()),
+        (
+            &ref_visitor,
+            "by reference",
+            &generics_ref,
+            format!("&{} ", ENUM_CONV_LIFETIME),
+        ),
+        (
+            &mut_visitor,
+            "by mutable reference",
+            &generics_ref,
+            format!("&{} mut ", ENUM_CONV_LIFETIME),
+        ),
+    ] {
+        let mut trait_methods = String::new();
+        for (info, method) in methods.iter() {
+            trait_methods.push_str(&format!(
+                "    {}fn {}(&mut self, value: {}{}) -> Self::Output;\n",
+                info.cfg, method, prefix, info.ty
+            ));
+        }
+        let mut context = Context::new();
+        context.insert("Attrs", cfg);
+        context.insert("name", name);
+        context.insert("Mode", mode);
+        context.insert("vis", vis);
+        context.insert("Trait", trait_name);
+        context.insert("generics", generics);
+        context.insert("Where", where_clause);
+        context.insert("Methods", &trait_methods);
+        impl_string.push_str(
+            &templater
+                .render("visitor", &context)
+                .expect("Failed to render the Visitor template"),
+        );
+    }
+
+    let mut arms = String::new();
+    for (info, method) in methods.iter() {
+        arms.push_str(&format!(
+            "            {}{}::{}(value) => visitor.{}(value),\n",
+            info.cfg, name, info.name, method
+        ));
+    }
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("vis", vis);
+    context.insert("generics", impl_generics);
+    context.insert("fullname", fullname);
+    context.insert("Where", where_clause);
+    context.insert("Param", ENUM_CONV_TYPE_PARAM);
+    context.insert("Lifetime", ENUM_CONV_LIFETIME);
+    context.insert("Visitor", &visitor);
+    context.insert("RefVisitor", &ref_visitor);
+    context.insert("MutVisitor", &mut_visitor);
+    context.insert("args", args);
+    context.insert("args_ref", &args_ref);
+    context.insert("Arms", &arms);
+    impl_string.push_str(
+        &templater
+            .render("accept", &context)
+            .expect("Failed to render the accept template"),
+    );
+    impl_string
+}
+
+/// Implement the conversions through the pointer of variants
+/// marked `#[Transparent]`: `From` the pointed to type and
+/// `TryTo` a reference to it. For a `Box`, the pointed to type
+/// can also be moved out or borrowed mutably. Unsized types
+/// such as `str` only get the conversion to a reference.
+pub(crate) fn impl_transparent(
+    name: &str,
+    fullname: &str,
+    impl_generics: &ImplGenerics,
+    error_config: &ErrorConfig,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        impl_generics_ref,
+        where_clause,
+    } = impl_generics;
+    let mut impl_string = String::new();
+    let (error, map) = error_config.to_template();
+    for info in variants.iter() {
+        let transparent = match &info.transparent {
+            Some(transparent) => transparent,
+            None => continue,
+        };
+        let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
+        context.insert("generics", impl_generics);
+        context.insert("generics_ref", impl_generics_ref);
+        context.insert("Type", &info.ty);
+        context.insert("Inner", &transparent.inner);
+        context.insert("Lifetime", ENUM_CONV_LIFETIME);
+        context.insert("fullname", fullname);
+        context.insert("field", &info.name);
+        context.insert("Where", where_clause);
+        context.insert("Error", &error);
+        context.insert("Map_Err", &map);
+        if !transparent.is_unsized {
+            impl_string.push_str(
+                &templater
+                    .render("transparent_from", &context)
+                    .expect("Failed to render the transparent From template"),
+            );
+        }
+        context.insert(
+            "Where",
+            &with_marker_bound(where_clause, name, fullname, info),
+        );
+        impl_string.push_str(
+            &templater
+                .render("transparent_ref", &context)
+                .expect("Failed to render the transparent TryTo template"),
+        );
+        if transparent.wrapper == Wrapper::Box && !transparent.is_unsized {
+            impl_string.push_str(
+                &templater
+                    .render("transparent_box", &context)
+                    .expect("Failed to render the transparent TryTo template"),
+            );
+        }
+    }
+    impl_string
+}
+
+/// Implement the pinned projections requested by `#[DerivePin]`:
+/// `TryTo<Pin<&mut T>> for Pin<&mut Enum>` and the same for shared
+/// references.
+///
+/// Pinning is only structural if the enum is not `Unpin` unless all
+/// of its variants are, and if it does not implement `Drop`, which
+/// could move the values out. To ensure the former, `Unpin` is
+/// implemented for the enum on the condition that a hidden struct
+/// holding all variant types is `Unpin`. Any other `Unpin` impl then
+/// conflicts with it. Likewise, a `Drop` impl makes a blanket impl
+/// of a hidden trait conflict with one for the enum.
+pub(crate) fn impl_pin(
+    name: &str,
+    fullname: &str,
+    impl_generics: &ImplGenerics,
+    error_config: &ErrorConfig,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        impl_generics_ref,
+        where_clause,
+    } = impl_generics;
+    let args = fullname.strip_prefix(name).unwrap_or_default();
+    let mut fields = String::new();
+    for info in variants.iter() {
+        fields.push_str(&format!(
+            "        {}_variant_{}: std::marker::PhantomData<{}>,\n",
+            info.cfg, info.index, info.ty
+        ));
+    }
+    let unpin = format!(
+        "__EnumConvUnpin{}",
+        prepend_lifetime(args, ENUM_CONV_LIFETIME)
+    );
+    let unpin_where = if where_clause.is_empty() {
+        format!("where {}: std::marker::Unpin", unpin)
+    } else {
+        format!("{}, {}: std::marker::Unpin", where_clause, unpin)
+    };
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("name", name);
+    context.insert("generics", impl_generics);
+    context.insert(
+        "generics_lt",
+        &prepend_lifetime(impl_generics, ENUM_CONV_LIFETIME),
+    );
+    context.insert("Lifetime", ENUM_CONV_LIFETIME);
+    context.insert("fullname", fullname);
+    context.insert("Where", where_clause);
+    context.insert("Unpin_Where", &unpin_where);
+    context.insert("Fields", &fields);
+    let mut impl_string = templater
+        .render("pin_checks", &context)
+        .expect("Failed to render the pin checks template");
+
+    let (error, map) = error_config.to_template();
+    for info in variants.iter() {
+        let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
+        context.insert("generics_ref", impl_generics_ref);
+        context.insert("Type", &info.ty);
+        context.insert("Lifetime", ENUM_CONV_LIFETIME);
+        context.insert("fullname", fullname);
+        context.insert(
+            "Where",
+            &with_marker_bound(where_clause, name, fullname, info),
+        );
+        context.insert("Error", &error);
+        context.insert("Map_Err", &map);
+        impl_string.push_str(
+            &templater
+                .render("try_to_pin", &context)
+                .expect("Failed to render the pinned TryTo template"),
+        );
+    }
+    impl_string
+}
+
+/// Implement the methods converting between the enum and the
+/// views requested by `#[DeriveViews]`: `as_ref_enum` and
+/// `as_mut_enum` on the enum, and `to_owned_enum` on the views.
+/// The views are `Copy` and `Clone` respectively, regardless
+/// of the types of the variants.
+pub(crate) fn impl_views(
+    name: &str,
+    fullname: &str,
+    vis: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let args = fullname.strip_prefix(name).unwrap_or_default();
+    let view_ref = get_view(name, false);
+    let view_mut = get_view(name, true);
+    let mut ref_arms = String::new();
+    let mut mut_arms = String::new();
+    let mut to_owned_ref_arms = String::new();
+    let mut to_owned_mut_arms = String::new();
+    for info in variants.iter() {
+        let VariantInfo {
+            name: field,
+            cfg: var_cfg,
+            ..
+        } = info;
+        for (arms, from, to) in [
+            (&mut ref_arms, name, &view_ref),
+            (&mut mut_arms, name, &view_mut),
+        ] {
+            arms.push_str(&format!(
+                "            {}{}::{}(value) => {}::{}(value),\n",
+                var_cfg, from, field, to, field
+            ));
+        }
+        for (arms, from) in [
+            (&mut to_owned_ref_arms, &view_ref),
+            (&mut to_owned_mut_arms, &view_mut),
+        ] {
+            arms.push_str(&format!(
+                "            {}{}::{}(value) => {}::{}(Clone::clone(&**value)),\n",
+                var_cfg, from, field, name, field
+            ));
+        }
+    }
+    let bounded = variant_bounds(name, ENUM_VIEW_LIFETIME, impl_generics, variants);
+    let clone_bounds: String = bounded
+        .iter()
+        .map(|ty| format!("        {}: Clone,\n", ty))
+        .collect();
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("vis", vis);
+    context.insert("generics", impl_generics);
+    context.insert(
+        "generics_view",
+        &prepend_lifetime(impl_generics, ENUM_VIEW_LIFETIME),
+    );
+    context.insert("fullname", fullname);
+    context.insert("Where", where_clause);
+    context.insert("Ref", &view_ref);
+    context.insert("Mut", &view_mut);
+    context.insert("args_anon", &prepend_lifetime(args, "'_"));
+    context.insert("args_view", &prepend_lifetime(args, ENUM_VIEW_LIFETIME));
+    context.insert("RefArms", &ref_arms);
+    context.insert("MutArms", &mut_arms);
+    context.insert("ToOwnedRefArms", &to_owned_ref_arms);
+    context.insert("ToOwnedMutArms", &to_owned_mut_arms);
+    context.insert("CloneBounds", &clone_bounds);
+    templater
+        .render("views", &context)
+        .expect("Failed to render the views template")
+}
+
+/// The types to bound on so that a trait is required for the type
+/// of each variant.
+///
+/// The bounds go through a lifetime, which must be a parameter of
+/// the item they are placed on, as a bound without any generic
+/// parameters that does not hold, such as `String: Copy`, is an
+/// error. `enum_conversion_traits::Deferred` gives back the
+/// variant's type through a projection involving the lifetime.
+///
+/// Attributes in where clauses are unstable, so the bounds for
+/// variants with `#[cfg]` attributes go through the aliases made
+/// by [`impl_bound_aliases`] instead.
+fn variant_bounds(
+    name: &str,
+    lifetime: &str,
+    impl_generics: &str,
+    variants: &[VariantInfo],
+) -> Vec<String> {
+    let (_, alias_args, _) = bare_generics(impl_generics, lifetime);
+    variants
+        .iter()
+        .map(|info| {
+            if info.cfg.is_empty() {
+                format!(
+                    "enum_conversion_traits::Deferred<{}, {}>",
+                    lifetime, info.ty
+                )
+            } else {
+                format!(
+                    "{}{}",
+                    get_marker(name, &get_bound_alias(&info.name)),
+                    alias_args
+                )
+            }
+        })
+        .collect()
+}
+
+/// Implement the aliases used by [`variant_bounds`] for the variants
+/// with `#[cfg]` attributes. Each is the variant's type if the variant
+/// exists and a type that implements every trait we bound on if not.
+/// They are placed in the module of the marker types.
+pub(crate) fn impl_bound_aliases(
+    impl_generics: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let (alias_generics, _, phantom) = bare_generics(impl_generics, ENUM_CONV_LIFETIME);
+    let mut aliases = String::new();
+    for info in variants.iter().filter(|info| !info.cfg.is_empty()) {
+        let mut context = Context::new();
+        context.insert("Cfg", &info.cfg);
+        context.insert("NotCfg", &negate_cfg(&info.cfg));
+        context.insert("Alias", &get_bound_alias(&info.name));
+        context.insert("generics", &alias_generics);
+        context.insert("Lifetime", ENUM_CONV_LIFETIME);
+        context.insert("Type", &info.ty);
+        context.insert("Phantom", &phantom);
+        aliases.push_str(
+            &templater
+                .render("variant_bound", &context)
+                .expect("Failed to render the variant_bound template"),
+        );
+    }
+    aliases
+}
+
+/// The generic parameters of the enum, preceded by a lifetime
+/// and stripped of bounds and defaults, for declaring type aliases,
+/// and the arguments for using them. Also returns a `PhantomData`
+/// using each of them, as type aliases must use all of their type
+/// parameters.
+fn bare_generics(impl_generics: &str, lifetime: &str) -> (String, String, String) {
+    let generics: syn::Generics = if impl_generics.is_empty() {
+        syn::Generics::default()
+    } else {
+        syn::parse_str(impl_generics).expect("Failed to parse the generics of the enum")
+    };
+    let mut params = vec![lifetime.to_string()];
+    let mut args = vec![lifetime.to_string()];
+    let mut used = vec![format!("&{} ()", lifetime)];
+    for param in generics.params.iter() {
+        match param {
+            syn::GenericParam::Lifetime(def) => {
+                params.push(def.lifetime.to_string());
+                args.push(def.lifetime.to_string());
+                used.push(format!("&{} ()", def.lifetime));
+            }
+            syn::GenericParam::Type(def) => {
+                params.push(def.ident.to_string());
+                args.push(def.ident.to_string());
+                used.push(format!("*const {}", def.ident));
+            }
+            syn::GenericParam::Const(def) => {
+                params.push(format!("const {}: {}", def.ident, def.ty.to_token_stream()));
+                args.push(def.ident.to_string());
+                used.push(format!("[(); {}]", def.ident));
+            }
+        }
+    }
+    (
+        format!("<{}>", params.join(", ")),
+        format!("<{}>", args.join(", ")),
+        format!("std::marker::PhantomData<({},)>", used.join(", ")),
+    )
+}
+
+/// Appends bounds to a where clause, which may be empty.
+fn with_bounds(where_clause: &str, bounds: &[String]) -> String {
+    if bounds.is_empty() {
+        return where_clause.to_string();
+    }
+    let separator = if where_clause.is_empty() {
+        "where\n "
+    } else {
+        ",\n "
+    };
+    format!("{}{}{}", where_clause, separator, bounds.join(",\n "))
+}
+
+/// Implement the buckets requested by `#[DeriveBuckets]`, a struct
+/// with a `Vec` for the values of each variant and one recording
+/// the order in which they were added.
+///
+/// The buckets are `Debug` and `Clone` if the types of all
+/// variants are.
+pub(crate) fn impl_buckets(
+    name: &str,
+    fullname: &str,
+    vis: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    if variants.iter().any(|info| info.name == "order") {
+        panic!(
+            "Cannot derive buckets for enum `{}` as its variant `order` clashes \
+             with the field recording the order of the values.",
+            name
+        );
+    }
+    let buckets = format!("{}Buckets", name);
+    let args = fullname.strip_prefix(name).unwrap_or_default();
+    let bounded = variant_bounds(name, ENUM_CONV_LIFETIME, impl_generics, variants);
+    let bounds = |bound: &str| {
+        let bounds = bounded
+            .iter()
+            .map(|ty| format!("{}: {}", ty, bound))
+            .collect::<Vec<String>>();
+        with_bounds(where_clause, &bounds)
+    };
+
+    let mut fields = String::new();
+    let mut debug_fields = String::new();
+    let mut clone_fields = String::new();
+    let mut new_fields = String::new();
+    let mut push_arms = String::new();
+    let mut bindings = String::new();
+    let mut into_iters = String::new();
+    let mut order_arms = String::new();
+    let mut impl_string = String::new();
+    for info in variants.iter() {
+        let VariantInfo {
+            index,
+            name: field,
+            ty,
+            cfg: var_cfg,
+            ..
+        } = info;
+        fields.push_str(&format!(
+            "    {}{}: std::vec::Vec<{}>,\n",
+            var_cfg, field, ty
+        ));
+        new_fields.push_str(&format!(
+            "            {}{}: std::vec::Vec::new(),\n",
+            var_cfg, field
+        ));
+        debug_fields.push_str(&format!(
+            "        {}debug.field(\"{}\", &self.{});\n",
+            var_cfg, field, field
+        ));
+        clone_fields.push_str(&format!(
+            "            {}{}: self.{}.clone(),\n",
+            var_cfg, field, field
+        ));
+        push_arms.push_str(&format!(
+            "            {}{}::{}(value) => {{\n                \
+             self.order.push({});\n                \
+             self.{}.push(value);\n            }}\n",
+            var_cfg, name, field, index, field
+        ));
+        bindings.push_str(&format!(
+            "            {}{}: bucket_{},\n",
+            var_cfg, field, index
+        ));
+        into_iters.push_str(&format!(
+            "        {}let mut bucket_{} = bucket_{}.into_iter();\n",
+            var_cfg, index, index
+        ));
+        order_arms.push_str(&format!(
+            "            {}{} => {}::{}(bucket_{}.next().expect(\"Each index in the order has a value.\")),\n",
+            var_cfg, index, name, field, index
+        ));
+
+        let mut context = Context::new();
+        context.insert("Attrs", &info.attrs);
+        context.insert("generics", impl_generics);
+        context.insert("Where", where_clause);
+        context.insert("Buckets", &buckets);
+        context.insert("args", args);
+        context.insert("Type", ty);
+        context.insert("bucket", field);
+        impl_string.push_str(
+            &templater
+                .render("bucket", &context)
+                .expect("Failed to render the Bucket template"),
+        );
+    }
+    let mut context = Context::new();
+    context.insert("Attrs", cfg);
+    context.insert("name", name);
+    context.insert("vis", vis);
+    context.insert("Buckets", &buckets);
+    context.insert("generics", impl_generics);
+    context.insert(
+        "generics_bound",
+        &prepend_lifetime(impl_generics, ENUM_CONV_LIFETIME),
+    );
+    context.insert("args", args);
+    context.insert("Where", where_clause);
+    context.insert("fullname", fullname);
+    context.insert("Param", ENUM_CONV_TYPE_PARAM);
+    context.insert("Fields", &fields);
+    context.insert("NewFields", &new_fields);
+    context.insert("PushArms", &push_arms);
+    context.insert("Bindings", &bindings);
+    context.insert("IntoIters", &into_iters);
+    context.insert("OrderArms", &order_arms);
+    context.insert("DebugWhere", &bounds("std::fmt::Debug"));
+    context.insert("DebugFields", &debug_fields);
+    context.insert("CloneWhere", &bounds("std::clone::Clone"));
+    context.insert("CloneFields", &clone_fields);
+    let buckets = templater
+        .render("buckets", &context)
+        .expect("Failed to render the buckets template");
+    format!("{}{}", buckets, impl_string)
+}
+
+/// Adds type parameters after the parameters of a list of
+/// generic parameters, e.g. `<'a, T>` becomes `<'a, T, U>`.
+fn append_type_params(generics: &str, params: &[&str]) -> String {
+    match generics.strip_suffix('>') {
+        Some(rest) => format!("{}, {}>", rest, params.join(", ")),
+        None => format!("<{}>", params.join(", ")),
+    }
+}
+
+/// Implement `AllowedTransition` for the pairs of types declared
+/// by `#[Transitions(Old => New, ..)]`, or for all pairs of types
+/// if the attribute is absent.
+///
+/// Panics if a declared type is not held by a variant.
+pub(crate) fn impl_transitions(
+    fullname: &str,
+    impl_generics: &ImplGenerics,
+    cfg: &str,
+    transitions: Option<&[(String, String)]>,
+    variants: &[VariantInfo],
+    templater: &Tera,
+) -> String {
+    let ImplGenerics {
+        impl_generics,
+        where_clause,
+        ..
+    } = impl_generics;
+    let render = |attrs: &str, generics: &str, old: &str, new: &str| {
+        let mut context = Context::new();
+        context.insert("Attrs", attrs);
+        context.insert("generics", generics);
+        context.insert("Where", where_clause);
+        context.insert("fullname", fullname);
+        context.insert("Old", old);
+        context.insert("New", new);
+        templater
+            .render("transition", &context)
+            .expect("Failed to render the AllowedTransition template")
+    };
+    let Some(transitions) = transitions else {
+        let old = format!("{}Old", ENUM_CONV_TYPE_PARAM);
+        let new = format!("{}New", ENUM_CONV_TYPE_PARAM);
+        let generics = append_type_params(impl_generics, &[&old, &new]);
+        return render(cfg, &generics, &old, &new);
+    };
+    let find = |ty: &str| {
+        variants
+            .iter()
+            .find(|info| info.ty == ty)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Transitions: `{}` is not the type of a variant of `{}`",
+                    pretty_type(ty),
+                    fullname
+                )
+            })
+    };
+    let mut impl_string = String::new();
+    for (old, new) in transitions {
+        let (old, new) = (find(old), find(new));
+        let attrs = format!("{}{}", old.attrs, new.cfg);
+        impl_string.push_str(&render(&attrs, impl_generics, &old.ty, &new.ty));
+    }
+    impl_string
+}
+
 #[cfg(test)]
 mod test_impls {
     use quote::quote;
@@ -193,15 +1104,38 @@ mod test_impls {
         )
         .expect("Test failed");
         let name = &ast.ident.to_string();
-        let (fullname, lifetimes) = fetch_name_with_generic_params(&mut ast);
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
-        let output = impl_get_variant(&name, &fullname, &impl_generics, &field_map, &tera);
+        let output = impl_get_variant(name, &fullname, &impl_generics, &variants, &tera);
         let expected = "\nimpl< 'a , T > enum_conversion_traits::GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field > for Enum<'a,T>\nwhere T : Debug {\n    #[allow(unreachable_patterns)]\n    fn get_variant(self) -> std::result::Result<Box < & 'a dyn Into < T > >, enum_conversion_traits::EnumConversionError> {\n        match self {\n            Enum::Field(inner) => Ok(inner),\n            _ => Err(enum_conversion_traits::EnumConversionError::new(\"Enum<'a,T>\", \"Box < & 'a dyn Into < T > >\"))\n        }\n    }\n\n    #[allow(unreachable_patterns)]\n    fn get_variant_ref(&self) -> std::result::Result<&Box < & 'a dyn Into < T > >, enum_conversion_traits::EnumConversionError> {\n        match &self {\n            Enum::Field(inner) => Ok(inner),\n            _ => Err(enum_conversion_traits::EnumConversionError::new(\"Enum<'a,T>\", \"Box < & 'a dyn Into < T > >\"))\n        }\n    }\n\n    #[allow(unreachable_patterns)]\n    fn get_variant_mut(&mut self) -> std::result::Result<&mut Box < & 'a dyn Into < T > >, enum_conversion_traits::EnumConversionError> {\n        match self {\n            Enum::Field(inner) => Ok(inner),\n            _  => Err(enum_conversion_traits::EnumConversionError::new(\"Enum<'a,T>\", \"Box < & 'a dyn Into < T > >\"))\n        }\n    }\n}\n";
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn test_contains_variant() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum<'a, T>
+            where
+                T: Debug
+            {
+                Field(Box<&'a dyn Into<T>>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_contains_variant(name, &fullname, &impl_generics, &variants, &tera);
+        let expected = "\nimpl< 'a , T > enum_conversion_traits::ContainsVariant<Box < & 'a dyn Into < T > >> for Enum<'a,T>\nwhere T : Debug {\n    type Marker = enum___conversion___Enum::Field;\n}\n";
+        assert_eq!(output, expected);
+    }
+
     #[test]
     fn test_try_from_off() {
         let mut ast: DeriveInput = syn::parse_str(
@@ -219,14 +1153,14 @@ mod test_impls {
         let error_config = ErrorConfig::default();
         let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
         let output = impl_try_from(
-            &name,
+            name,
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
             &tera,
         );
         assert!(output.is_empty());
@@ -251,20 +1185,19 @@ mod test_impls {
         )
         .expect("Test failed");
         let name = &ast.ident.to_string();
-        let error_config =
-            parse_custom_error_config(quote!(Error: Box<dyn Error + 'static>, |e| e
+        let error_config = parse_custom_error_config(quote!(Error: Box<dyn Error + 'static>, |e| e
                 .to_string()
                 .into()));
         let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
         let output = impl_try_from(
-            &name,
+            name,
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
             &tera,
         );
         let expected = "\nimpl< 'a , T > TryFrom<Enum<'a,T>> for Box < & 'a dyn Into < T > >\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_from(value: Enum<'a,T>) -> std::result::Result<Self, Self::Error> {\n        value.try_to()\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryFrom<&'enum_conv Enum<'a,T>> for &'enum_conv Box < & 'a dyn Into < T > >\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_from(value: &'enum_conv Enum<'a,T>) -> std::result::Result<Self, Self::Error> {\n        value.try_to()\n\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryFrom<&'enum_conv mut Enum<'a,T>> for &'enum_conv mut Box < & 'a dyn Into < T > >\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_from(value: &'enum_conv mut Enum<'a,T>) -> std::result::Result<Self, Self::Error> {\n        value.try_to()\n    }\n}\n";
@@ -290,14 +1223,14 @@ mod test_impls {
         let error_config = ErrorConfig::default();
         let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
         let output = impl_try_from(
-            &name,
+            name,
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
             &tera,
         );
         let expected = "\nimpl< 'a , T > TryFrom<Enum<'a,T>> for i64\nwhere T : Debug,\n Enum<'a,T>: GetVariant<i64, enum___conversion___Enum::Other>\n{\n    type Error = EnumConversionError;\n\n    fn try_from(value: Enum<'a,T>) -> std::result::Result<Self, Self::Error> {\n        value.try_to()\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryFrom<&'enum_conv Enum<'a,T>> for &'enum_conv i64\nwhere T : Debug,\n Enum<'a,T>: GetVariant<i64, enum___conversion___Enum::Other>\n{\n    type Error = EnumConversionError;\n\n    fn try_from(value: &'enum_conv Enum<'a,T>) -> std::result::Result<Self, Self::Error> {\n        value.try_to()\n\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryFrom<&'enum_conv mut Enum<'a,T>> for &'enum_conv mut i64\nwhere T : Debug,\n Enum<'a,T>: GetVariant<i64, enum___conversion___Enum::Other>\n{\n    type Error = EnumConversionError;\n\n    fn try_from(value: &'enum_conv mut Enum<'a,T>) -> std::result::Result<Self, Self::Error> {\n        value.try_to()\n    }\n}\n";
@@ -319,13 +1252,41 @@ mod test_impls {
         .expect("Test failed");
         let (fullname, lifeftimes) = fetch_name_with_generic_params(&ast);
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifeftimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
-        let output = impl_from(&fullname, &impl_generics, &field_map, &tera);
+        let output = impl_from(&fullname, &impl_generics, &variants, &tera);
         let expected = "\nimpl< 'a , T > From<Box < & 'a dyn Into < T > >> for Enum<'a,T>\nwhere T : Debug\n{\n    fn from(value: Box < & 'a dyn Into < T > >) -> Self {\n        Self::Field(value)\n    }\n}\n";
         assert_eq!(output, expected);
     }
 
+    /// The impls are generated in the order the variants are declared.
+    #[test]
+    fn test_from_order() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                Zebra(u64),
+                Aardvark(u32),
+                Moose(u16),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_from(&fullname, &impl_generics, &variants, &tera);
+        let types = output
+            .lines()
+            .filter_map(|line| line.strip_prefix("impl From<"))
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            types,
+            vec!["u64> for Enum", "u32> for Enum", "u16> for Enum"]
+        );
+    }
+
     #[test]
     fn test_try_to() {
         let mut ast: DeriveInput = syn::parse_str(
@@ -343,17 +1304,17 @@ mod test_impls {
         let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
         let error_config = ErrorConfig::default();
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
         let output = impl_try_to(
             &name,
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
             &tera,
         );
-        let expected = "\nimpl< 'a , T > TryTo<Box < & 'a dyn Into < T > >> for Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant()\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv Box < & 'a dyn Into < T > >> for &'enum_conv Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_ref()\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv mut Box < & 'a dyn Into < T > >> for &'enum_conv mut Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv mut Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_mut()\n    }\n}\n";
+        let expected = "\nimpl< 'a , T > TryTo<Box < & 'a dyn Into < T > >> for Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant()\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv Box < & 'a dyn Into < T > >> for &'enum_conv Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_ref()\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv mut Box < & 'a dyn Into < T > >> for &'enum_conv mut Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv mut Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_mut()\n    }\n}\n\nimpl< 'a , T > TryTo<Box < & 'a dyn Into < T > >> for std::boxed::Box<Enum<'a,T>>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = EnumConversionError;\n\n    fn try_to(self) -> std::result::Result<Box < & 'a dyn Into < T > >, Self::Error> {\n        (*self).get_variant()\n    }\n}\n";
         assert_eq!(output, expected);
     }
 
@@ -375,23 +1336,426 @@ mod test_impls {
         )
         .expect("Test failed");
         let name = ast.ident.to_string();
-        let error_config =
-            parse_custom_error_config(quote!(Error: Box<dyn Error + 'static>, |e| e
+        let error_config = parse_custom_error_config(quote!(Error: Box<dyn Error + 'static>, |e| e
                 .to_string()
                 .into()));
         let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
         let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
-        let field_map = fetch_fields_from_enum(&mut ast);
+        let variants = fetch_fields_from_enum(&mut ast);
         let tera = templater();
         let output = impl_try_to(
             &name,
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
+            &tera,
+        );
+        let expected = "\nimpl< 'a , T > TryTo<Box < & 'a dyn Into < T > >> for Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant().map_err(| e | e . to_string () . into ())\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv Box < & 'a dyn Into < T > >> for &'enum_conv Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_ref().map_err(| e | e . to_string () . into ())\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv mut Box < & 'a dyn Into < T > >> for &'enum_conv mut Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv mut Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_mut().map_err(| e | e . to_string () . into ())\n    }\n}\n\nimpl< 'a , T > TryTo<Box < & 'a dyn Into < T > >> for std::boxed::Box<Enum<'a,T>>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<Box < & 'a dyn Into < T > >, Self::Error> {\n        (*self).get_variant().map_err(| e | e . to_string () . into ())\n    }\n}\n";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_variants() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            #[cfg(unix)]
+            enum Enum<T> {
+                Int(i32),
+                #[cfg(feature = "list")]
+                List(Vec<T>),
+                Text(&'static str),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let cfg = fetch_propagated_attrs(&ast.attrs).cfg;
+        let tera = templater();
+        let output = impl_variants(name, &fullname, &impl_generics, &cfg, &variants, &tera);
+        let expected = "\n# [cfg (unix)] #[allow(deprecated)]\nimpl< T > enum_conversion_traits::Variants for Enum<T>\n {\n    const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();\n    const VARIANT_NAMES: &'static [&'static str] = &[\"Int\", # [cfg (feature = \"list\")] \"List\", \"Text\", ];\n    const VARIANT_TYPE_NAMES: &'static [&'static str] = &[\"i32\", # [cfg (feature = \"list\")] \"Vec<T>\", \"&'static str\", ];\n\n    fn variant_index(&self) -> usize {\n        match *self {\n            Enum::Int(_) => 0,\n            # [cfg (feature = \"list\")] Enum::List(_) => 1,\n            Enum::Text(_) => { let mut index = 0; { index += 1; } # [cfg (feature = \"list\")] { index += 1; } index },\n        }\n    }\n}\n";
+        assert!(output.starts_with(expected));
+        assert!(output.contains(
+            "\n# [cfg (unix)] # [cfg (feature = \"list\")] impl enum_conversion_traits::MarkerIndex for enum___conversion___Enum::List {\n    const INDEX: usize = 1;\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_kind() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub enum Enum {
+                Int(i32),
+                #[cfg(feature = "text")]
+                Text(String),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let vis = ast.vis.to_token_stream().to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_kind(name, &fullname, &vis, &impl_generics, "", &variants, &tera);
+        assert!(output
+            .contains("pub enum EnumKind {\n    Int,\n    # [cfg (feature = \"text\")] Text,\n}"));
+        assert!(output.contains(
+            "pub const ALL: &'static [EnumKind] = &[EnumKind::Int, # [cfg (feature = \"text\")] EnumKind::Text, ];"
+        ));
+        assert!(output.contains(
+            "            # [cfg (feature = \"text\")] Enum::Text(_) => EnumKind::Text,\n"
+        ));
+        assert!(output.contains(
+            "\n# [cfg (feature = \"text\")] impl enum_conversion_traits::MarkerKind<EnumKind> for enum___conversion___Enum::Text {\n    const KIND: EnumKind = EnumKind::Text;\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_set() {
+        let variants = (0..65)
+            .map(|index| {
+                VariantInfo::new(index, &format!("F{}", index), &format!("[u8; {}]", index))
+            })
+            .collect::<Vec<VariantInfo>>();
+        let impl_generics = ImplGenerics {
+            impl_generics: "".to_string(),
+            impl_generics_ref: "<'enum_conv>".to_string(),
+            where_clause: "".to_string(),
+        };
+        let output = impl_set(
+            "Enum",
+            "Enum",
+            "pub",
+            &impl_generics,
+            "",
+            &variants,
+            &templater(),
+        );
+        assert!(output.contains("pub struct EnumSet\n {\n    bits: [u64; 2],\n"));
+        assert!(output.contains(
+            "    pub fn insert<EnumConvT>(&mut self) -> bool\n    where\n        \
+             Enum: enum_conversion_traits::ContainsVariant<EnumConvT>,\n        \
+             <Enum as enum_conversion_traits::ContainsVariant<EnumConvT>>::Marker: enum_conversion_traits::MarkerIndex,\n    {\n        \
+             self.insert_index(<<Enum as enum_conversion_traits::ContainsVariant<EnumConvT>>::Marker as enum_conversion_traits::MarkerIndex>::INDEX)\n    }"
+        ));
+        assert!(output.contains(
+            "    pub const fn all() -> Self {\n        Self::from_kinds(EnumKind::ALL)\n    }"
+        ));
+    }
+
+    #[test]
+    fn test_visitor() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub enum Enum<T> {
+                Int(i32),
+                ListOfT(Vec<T>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let vis = ast.vis.to_token_stream().to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_visitor(name, &fullname, &vis, &impl_generics, "", &variants, &tera);
+        assert!(output.contains(
+            "pub trait EnumVisitor< T >\n {\n    /// The type returned by the methods of the visitor.\n    type Output;\n    fn visit_int(&mut self, value: i32) -> Self::Output;\n    fn visit_list_of_t(&mut self, value: Vec < T >) -> Self::Output;\n}"
+        ));
+        assert!(output.contains(
+            "pub trait EnumMutVisitor<'enum_conv,  T >\n {\n    /// The type returned by the methods of the visitor.\n    type Output;\n    fn visit_int(&mut self, value: &'enum_conv mut i32) -> Self::Output;\n    fn visit_list_of_t(&mut self, value: &'enum_conv mut Vec < T >) -> Self::Output;\n}"
+        ));
+        assert!(output.contains(
+            "pub fn accept_ref<'enum_conv, EnumConvT: EnumRefVisitor<'enum_conv, T>>(&'enum_conv self, visitor: &mut EnumConvT) -> EnumConvT::Output {"
+        ));
+        assert!(output
+            .contains("            Enum::ListOfT(value) => visitor.visit_list_of_t(value),\n"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot derive a visitor for enum `Enum` as the names of its variants \
+                    do not give distinct method names."
+    )]
+    fn test_visitor_method_collision() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                HttpRequest(i32),
+                HTTPRequest(i64),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        impl_visitor(name, &fullname, "", &impl_generics, "", &variants, &tera);
+    }
+
+    #[test]
+    fn test_transparent() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                #[Transparent]
+                Big(Box<Frame>),
+                #[Transparent]
+                Shared(Rc<str>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_transparent(
+            name,
+            &fullname,
+            &impl_generics,
+            &ErrorConfig::default(),
+            &variants,
+            &tera,
+        );
+        let headers = output
+            .lines()
+            .filter(|line| line.starts_with("impl"))
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            headers,
+            vec![
+                "impl From<Frame> for Enum",
+                "impl< 'enum_conv > TryTo<&'enum_conv Frame> for &'enum_conv Enum",
+                "impl TryTo<Frame> for Enum",
+                "impl< 'enum_conv > TryTo<&'enum_conv mut Frame> for &'enum_conv mut Enum",
+                "impl< 'enum_conv > TryTo<&'enum_conv str> for &'enum_conv Enum",
+            ]
+        );
+        assert!(output.contains("self.get_variant().map(|inner: Box < Frame >| *inner)"));
+    }
+
+    #[test]
+    fn test_views() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub enum Enum<T> {
+                Int(i32),
+                #[cfg(unix)]
+                List(Vec<T>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let vis = ast.vis.to_token_stream().to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_views(name, &fullname, &vis, &impl_generics, "", &variants, &tera);
+        assert!(output.contains(
+            "    pub fn as_ref_enum(&self) -> EnumRef<'_, T> {\n        match self {\n            Enum::Int(value) => EnumRef::Int(value),\n            # [cfg (unix)] Enum::List(value) => EnumRef::List(value),\n        }\n    }"
+        ));
+        assert!(output.contains(
+            "    pub fn to_owned_enum(&self) -> Enum<T>\n    where\n        enum_conversion_traits::Deferred<'enum_view, i32>: Clone,\n        enum___conversion___Enum::bound___List<'enum_view, T>: Clone,\n    {"
+        ));
+        assert!(output.contains("impl<'enum_view,  T > Copy for EnumRef<'enum_view, T>"));
+    }
+
+    /// Only the variants with `#[cfg]` attributes get an alias.
+    #[test]
+    fn test_bound_aliases() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub enum Enum<T> {
+                Int(i32),
+                #[cfg(unix)]
+                List(Vec<T>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let (_, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let output = impl_bound_aliases(&impl_generics.impl_generics, &variants, &templater());
+        let expected = "\n# [cfg (unix)] #[doc(hidden)]\n#[allow(non_camel_case_types)]\npub type bound___List<'enum_conv, T> = enum_conversion_traits::Deferred<'enum_conv, Vec < T >, std::marker::PhantomData<(&'enum_conv (), *const T,)>>;\n\n#[cfg(not(all(unix)))] #[doc(hidden)]\n#[allow(non_camel_case_types)]\npub type bound___List<'enum_conv, T> = std::marker::PhantomData<(&'enum_conv (), *const T,)>;\n";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_buckets() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub enum Enum<T> {
+                Int(i32),
+                #[cfg(unix)]
+                List(Vec<T>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let vis = ast.vis.to_token_stream().to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_buckets(name, &fullname, &vis, &impl_generics, "", &variants, &tera);
+        assert!(output.contains(
+            "pub struct EnumBuckets< T >\n {\n    order: std::vec::Vec<usize>,\n    Int: std::vec::Vec<i32>,\n    # [cfg (unix)] List: std::vec::Vec<Vec < T >>,\n}"
+        ));
+        assert!(output.contains(
+            "            # [cfg (unix)] Enum::List(value) => {\n                self.order.push(1);\n                self.List.push(value);\n            }\n"
+        ));
+        assert!(output.contains(
+            "            # [cfg (unix)] 1 => Enum::List(bucket_1.next().expect(\"Each index in the order has a value.\")),\n"
+        ));
+        assert!(output.contains(
+            "impl<'enum_conv,  T > std::clone::Clone for EnumBuckets<T>\nwhere\n enum_conversion_traits::Deferred<'enum_conv, i32>: std::clone::Clone,\n enum___conversion___Enum::bound___List<'enum_conv, T>: std::clone::Clone {"
+        ));
+        assert!(output.contains(
+            "# [cfg (unix)] impl< T > enum_conversion_traits::Bucket<Vec < T >> for EnumBuckets<T>\n {"
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot derive buckets for enum `Enum` as its variant `order` clashes \
+                    with the field recording the order of the values."
+    )]
+    fn test_buckets_order_collision() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                order(i32),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &[]);
+        let variants = fetch_fields_from_enum(&mut ast);
+        impl_buckets(
+            "Enum",
+            "Enum",
+            "",
+            &impl_generics,
+            "",
+            &variants,
+            &templater(),
+        );
+    }
+
+    #[test]
+    fn test_pin() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum<T> where T: Debug {
+                Int(i32),
+                #[cfg(unix)]
+                List(Vec<T>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let name = &ast.ident.to_string();
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_pin(
+            name,
+            &fullname,
+            &impl_generics,
+            &ErrorConfig::default(),
+            "",
+            &variants,
             &tera,
         );
-        let expected = "\nimpl< 'a , T > TryTo<Box < & 'a dyn Into < T > >> for Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant().map_err(| e | e . to_string () . into ())\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv Box < & 'a dyn Into < T > >> for &'enum_conv Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_ref().map_err(| e | e . to_string () . into ())\n    }\n}\n\nimpl< 'a , 'enum_conv : 'a , T , > TryTo<&'enum_conv mut Box < & 'a dyn Into < T > >> for &'enum_conv mut Enum<'a,T>\nwhere T : Debug,\n Enum<'a,T>: GetVariant<Box < & 'a dyn Into < T > >, enum___conversion___Enum::Field>\n{\n\n    type Error = Box < dyn Error + 'static >;\n\n    fn try_to(self) -> std::result::Result<&'enum_conv mut Box < & 'a dyn Into < T > >, Self::Error> {\n        self.get_variant_mut().map_err(| e | e . to_string () . into ())\n    }\n}\n";
+        let expected = "\nconst _: () = {\n    #[doc(hidden)]\n    #[allow(dead_code)]\n    pub struct __EnumConvUnpin<'enum_conv,  T >\n    where T : Debug {\n        _enum: std::marker::PhantomData<(&'enum_conv (), fn() -> Enum<T>)>,\n        _variant_0: std::marker::PhantomData<i32>,\n        # [cfg (unix)] _variant_1: std::marker::PhantomData<Vec < T >>,\n    }\n\n    impl<'enum_conv,  T > std::marker::Unpin for Enum<T>\n    where T : Debug, __EnumConvUnpin<'enum_conv, T>: std::marker::Unpin {}\n\n    trait EnumMustNotImplDrop {}\n\n    #[allow(drop_bounds)]\n    impl<T: std::ops::Drop> EnumMustNotImplDrop for T {}\n\n    impl< T > EnumMustNotImplDrop for Enum<T>\n    where T : Debug {}\n};\n";
+        assert!(output.starts_with(expected));
+        let headers = output
+            .lines()
+            .filter(|line| line.contains("TryTo<"))
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            headers,
+            vec![
+                "impl< 'enum_conv , T , > TryTo<std::pin::Pin<&'enum_conv i32>> for std::pin::Pin<&'enum_conv Enum<T>>",
+                "impl< 'enum_conv , T , > TryTo<std::pin::Pin<&'enum_conv mut i32>> for std::pin::Pin<&'enum_conv mut Enum<T>>",
+                "# [cfg (unix)] impl< 'enum_conv , T , > TryTo<std::pin::Pin<&'enum_conv Vec < T >>> for std::pin::Pin<&'enum_conv Enum<T>>",
+                "# [cfg (unix)] impl< 'enum_conv , T , > TryTo<std::pin::Pin<&'enum_conv mut Vec < T >>> for std::pin::Pin<&'enum_conv mut Enum<T>>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transitions() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum<T> where T: Debug {
+                Int(i32),
+                #[cfg(unix)]
+                List(Vec<T>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let tera = templater();
+        let output = impl_transitions(&fullname, &impl_generics, "", None, &variants, &tera);
+        let expected = "\nimpl< T , EnumConvTOld, EnumConvTNew> enum_conversion_traits::AllowedTransition<EnumConvTOld, EnumConvTNew> for Enum<T>\nwhere T : Debug {}\n";
         assert_eq!(output, expected);
+        let transitions = [
+            ("i32".to_string(), "Vec < T >".to_string()),
+            ("Vec < T >".to_string(), "i32".to_string()),
+        ];
+        let output = impl_transitions(
+            &fullname,
+            &impl_generics,
+            "",
+            Some(&transitions),
+            &variants,
+            &tera,
+        );
+        let expected = "\n# [cfg (unix)] impl< T > enum_conversion_traits::AllowedTransition<i32, Vec < T >> for Enum<T>\nwhere T : Debug {}\n\n# [cfg (unix)] impl< T > enum_conversion_traits::AllowedTransition<Vec < T >, i32> for Enum<T>\nwhere T : Debug {}\n";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transitions: `bool` is not the type of a variant of `Enum`")]
+    fn test_transitions_missing_type() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                Int(i32),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &[]);
+        let variants = fetch_fields_from_enum(&mut ast);
+        let transitions = [("i32".to_string(), "bool".to_string())];
+        impl_transitions(
+            "Enum",
+            &impl_generics,
+            "",
+            Some(&transitions),
+            &variants,
+            &templater(),
+        );
     }
 }
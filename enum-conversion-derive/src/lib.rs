@@ -2,23 +2,31 @@ mod impls;
 mod parse_attributes;
 mod parse_enum;
 mod templates;
+mod unify;
 
 extern crate proc_macro;
 
-use std::collections::HashMap;
-
 use proc_macro::TokenStream;
 use quote::ToTokens;
 use syn::DeriveInput;
 use tera::{Context, Tera};
 
-use crate::parse_attributes::{parse_custom_error_config, ErrorConfig};
+use crate::parse_attributes::{
+    fetch_propagated_attrs, parse_custom_error_config, parse_enum_options, parse_transitions,
+    ErrorConfig,
+};
 
 const ENUM_CONV_LIFETIME: &str = "'enum_conv";
+/// A type parameter for generated generic methods, chosen
+/// so as not to clash with the parameters of the enum.
+const ENUM_CONV_TYPE_PARAM: &str = "EnumConvT";
+/// The lifetime of the references held by the views of
+/// the enum generated by `#[DeriveViews]`.
+const ENUM_VIEW_LIFETIME: &str = "'enum_view";
 
 use crate::parse_enum::{
-    create_marker_enums, fetch_fields_from_enum, fetch_impl_generics,
-    fetch_name_with_generic_params, get_marker,
+    create_marker_enums, create_view_enum, fetch_fields_from_enum, fetch_impl_generics,
+    fetch_name_with_generic_params, get_marker, get_view,
 };
 
 #[proc_macro_attribute]
@@ -36,19 +44,79 @@ pub fn DeriveTryFrom(_: TokenStream, input: TokenStream) -> TokenStream {
     input
 }
 
-/// Implements ContainsVariant, GetVariant, SetVariant, and CreateVariantFrom traits
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn DeriveKind(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn DeriveVisitor(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn DerivePin(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn DeriveViews(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn DeriveVariantSet(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn DeriveBuckets(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn Transitions(_: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+/// Implements the GetVariant, ContainsVariant, TryFrom, TryTo, From, and Variants traits
 fn impl_conversions(error_config: ErrorConfig, mut ast: DeriveInput) -> TokenStream {
     let tera = templates::templater();
     let name = &ast.ident.to_string();
     let (fullname, lifetimes) = fetch_name_with_generic_params(&ast);
     let impl_generics = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
 
-    let field_map = fetch_fields_from_enum(&mut ast);
+    let options = parse_enum_options(&mut ast.attrs);
+    let transitions = parse_transitions(&mut ast.attrs);
+    let variants = fetch_fields_from_enum(&mut ast);
+    let cfg = fetch_propagated_attrs(&ast.attrs).cfg;
+    let vis = ast.vis.to_token_stream().to_string();
     let mut tokens: TokenStream = ast.to_token_stream().to_string().parse().unwrap();
 
-    tokens.extend::<TokenStream>(create_marker_enums(name, &field_map).parse().unwrap());
+    let aliases = if options.views || options.buckets {
+        impls::impl_bound_aliases(&impl_generics.impl_generics, &variants, &tera)
+    } else {
+        String::new()
+    };
     tokens.extend::<TokenStream>(
-        impls::impl_get_variant(name, &fullname, &impl_generics, &field_map, &tera)
+        create_marker_enums(name, &vis, &cfg, &variants, &aliases)
+            .parse()
+            .unwrap(),
+    );
+    tokens.extend::<TokenStream>(
+        impls::impl_get_variant(name, &fullname, &impl_generics, &variants, &tera)
+            .parse()
+            .unwrap(),
+    );
+    tokens.extend::<TokenStream>(
+        impls::impl_contains_variant(name, &fullname, &impl_generics, &variants, &tera)
             .parse()
             .unwrap(),
     );
@@ -58,7 +126,7 @@ fn impl_conversions(error_config: ErrorConfig, mut ast: DeriveInput) -> TokenStr
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
             &tera,
         )
         .parse()
@@ -70,16 +138,153 @@ fn impl_conversions(error_config: ErrorConfig, mut ast: DeriveInput) -> TokenStr
             &fullname,
             &impl_generics,
             &error_config,
-            &field_map,
+            &variants,
+            &tera,
+        )
+        .parse()
+        .unwrap(),
+    );
+    tokens.extend::<TokenStream>(
+        impls::impl_from(&fullname, &impl_generics, &variants, &tera)
+            .parse()
+            .unwrap(),
+    );
+    tokens.extend::<TokenStream>(
+        impls::impl_transparent(
+            name,
+            &fullname,
+            &impl_generics,
+            &error_config,
+            &variants,
             &tera,
         )
         .parse()
         .unwrap(),
     );
     tokens.extend::<TokenStream>(
-        impls::impl_from(&fullname, &impl_generics, &field_map, &tera)
+        impls::impl_variants(name, &fullname, &impl_generics, &cfg, &variants, &tera)
             .parse()
             .unwrap(),
     );
+    tokens.extend::<TokenStream>(
+        impls::impl_transitions(
+            &fullname,
+            &impl_generics,
+            &cfg,
+            transitions.as_deref(),
+            &variants,
+            &tera,
+        )
+        .parse()
+        .unwrap(),
+    );
+    if options.kind || options.set {
+        tokens.extend::<TokenStream>(
+            impls::impl_kind(
+                name,
+                &fullname,
+                &vis,
+                &impl_generics,
+                &cfg,
+                &variants,
+                &tera,
+            )
+            .parse()
+            .unwrap(),
+        );
+    }
+    if options.set {
+        tokens.extend::<TokenStream>(
+            impls::impl_set(
+                name,
+                &fullname,
+                &vis,
+                &impl_generics,
+                &cfg,
+                &variants,
+                &tera,
+            )
+            .parse()
+            .unwrap(),
+        );
+    }
+    if options.visitor {
+        tokens.extend::<TokenStream>(
+            impls::impl_visitor(
+                name,
+                &fullname,
+                &vis,
+                &impl_generics,
+                &cfg,
+                &variants,
+                &tera,
+            )
+            .parse()
+            .unwrap(),
+        );
+    }
+    if options.pin {
+        tokens.extend::<TokenStream>(
+            impls::impl_pin(
+                name,
+                &fullname,
+                &impl_generics,
+                &error_config,
+                &cfg,
+                &variants,
+                &tera,
+            )
+            .parse()
+            .unwrap(),
+        );
+    }
+    if options.buckets {
+        tokens.extend::<TokenStream>(
+            impls::impl_buckets(
+                name,
+                &fullname,
+                &vis,
+                &impl_generics,
+                &cfg,
+                &variants,
+                &tera,
+            )
+            .parse()
+            .unwrap(),
+        );
+    }
+    if options.views {
+        tokens.extend::<TokenStream>(
+            impls::impl_views(
+                name,
+                &fullname,
+                &vis,
+                &impl_generics,
+                &cfg,
+                &variants,
+                &tera,
+            )
+            .parse()
+            .unwrap(),
+        );
+        // The views get the same conversions as the enum.
+        for (mutable, reference) in [
+            (false, format!("&{}", ENUM_VIEW_LIFETIME)),
+            (true, format!("&{} mut", ENUM_VIEW_LIFETIME)),
+        ] {
+            let view = create_view_enum(
+                &ast,
+                &get_view(name, mutable),
+                ENUM_VIEW_LIFETIME,
+                &reference,
+                &cfg,
+                &variants,
+            );
+            tokens.extend(impl_conversions(
+                error_config.clone(),
+                syn::parse_str(&view).unwrap(),
+            ));
+        }
+    }
     tokens
 }
@@ -1,32 +1,268 @@
 use std::convert::TryFrom;
 
 use quote::ToTokens;
-use syn::parse::Parser;
+use syn::__private::TokenStream2;
+use syn::parse::{ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::Token;
-use syn::__private::TokenStream2;
-use syn::{Attribute, Expr};
+use syn::{Attribute, Expr, Meta, MetaList, NestedMeta, Type};
 
 const ATTR_TRY_FROM: &str = "DeriveTryFrom";
+const ARG_AUTO: &str = "auto";
+const ATTR_KIND: &str = "DeriveKind";
+const ATTR_VISITOR: &str = "DeriveVisitor";
+const ATTR_PIN: &str = "DerivePin";
+const ATTR_VIEWS: &str = "DeriveViews";
+const ATTR_BUCKETS: &str = "DeriveBuckets";
+const ATTR_SET: &str = "DeriveVariantSet";
+const ATTR_TRANSPARENT: &str = "Transparent";
+const ATTR_TRANSITIONS: &str = "Transitions";
+
+/// How the `TryFrom` traits should be derived. Given
+/// by the `#[DeriveTryFrom]` attribute on either the
+/// enum or one of its variants.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub(crate) enum TryFromConfig {
+    /// No attribute was found.
+    #[default]
+    Off,
+    /// `#[DeriveTryFrom]`: always derive the traits.
+    On,
+    /// `#[DeriveTryFrom(auto)]`: derive the traits only
+    /// if the orphan rules allow it.
+    Auto,
+}
+
+impl TryFromConfig {
+    /// A config on a variant takes precedence over
+    /// one on the enum.
+    pub(crate) fn or(self, global: TryFromConfig) -> TryFromConfig {
+        match self {
+            Self::Off => global,
+            _ => self,
+        }
+    }
+}
+
+/// Attributes on the enum or its variants that must
+/// also be placed on the generated items.
+const ATTRS_CFG: &str = "cfg";
+const ATTRS_CFG_ATTR: &str = "cfg_attr";
+const ATTRS_DEPRECATED: &str = "deprecated";
 
 /// The information for each variant
 /// in the enum.
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct VariantInfo {
+    /// The position of the variant in the enum's declaration.
+    pub index: usize,
+    /// The name of the variant.
+    pub name: String,
     /// The type of the variant.
     pub ty: String,
     /// Indicates if a `TryFrom` trait should be derived
     /// for this variant.
     pub try_from: bool,
+    /// Attributes placed on every item generated for this
+    /// variant. These are the `#[cfg]` attributes of the
+    /// enum and the variant, and `#[allow(deprecated)]` if
+    /// either is deprecated.
+    pub attrs: String,
+    /// The `#[deprecated]` attribute of the variant, if any.
+    /// It is placed on the variant's marker.
+    pub deprecated: String,
+    /// The `#[cfg]` attributes of the variant only, for items
+    /// generated once for the whole enum.
+    pub cfg: String,
+    /// Set by the `#[Transparent]` attribute if conversions
+    /// should also be derived for the type inside the
+    /// variant's `Box`, `Rc` or `Arc`.
+    pub transparent: Option<Transparent>,
+}
+
+/// The smart pointers whose contents can be converted
+/// to and from directly with `#[Transparent]`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub(crate) enum Wrapper {
+    Box,
+    Rc,
+    Arc,
+}
+
+/// The pointer type of a `#[Transparent]` variant and the
+/// type it points to.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct Transparent {
+    pub wrapper: Wrapper,
+    pub inner: String,
+    /// Whether the pointed to type is known to be unsized,
+    /// e.g. `str`, in which case it cannot be passed by value.
+    pub is_unsized: bool,
 }
 
-impl From<&str> for VariantInfo {
-    fn from(ty: &str) -> Self {
+impl VariantInfo {
+    pub(crate) fn new(index: usize, name: &str, ty: &str) -> Self {
         VariantInfo {
+            index,
+            name: name.to_string(),
             ty: ty.to_string(),
-            try_from: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// The `#[cfg]` and `#[deprecated]` attributes of an
+/// enum or variant, as strings.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct PropagatedAttrs {
+    pub cfg: String,
+    pub deprecated: String,
+}
+
+impl PropagatedAttrs {
+    /// Combines the attributes of the enum with those
+    /// of one of its variants into the attributes for
+    /// items generated for the variant.
+    pub(crate) fn for_variant(&self, variant: &PropagatedAttrs) -> String {
+        let mut attrs = format!("{}{}", self.cfg, variant.cfg);
+        if !self.deprecated.is_empty() || !variant.deprecated.is_empty() {
+            attrs.push_str("#[allow(deprecated)] ");
+        }
+        attrs
+    }
+}
+
+/// Fetch the attributes that must be copied onto the generated
+/// items. Unlike the attributes of this crate, these are not
+/// stripped from the AST.
+///
+/// `#[cfg]` and `#[deprecated]` attributes inside of `#[cfg_attr]`
+/// are found too, as attribute macros see their input before
+/// `#[cfg_attr]` is expanded. `#[cfg_attr(predicate, cfg(..))]`
+/// becomes `#[cfg(any(not(predicate), all(..)))]`.
+pub(crate) fn fetch_propagated_attrs(attrs: &[Attribute]) -> PropagatedAttrs {
+    let mut propagated = PropagatedAttrs::default();
+    for attr in attrs.iter() {
+        if attr.path.is_ident(ATTRS_CFG) {
+            propagated
+                .cfg
+                .push_str(&format!("{} ", attr.to_token_stream()));
+        } else if attr.path.is_ident(ATTRS_DEPRECATED) {
+            propagated
+                .deprecated
+                .push_str(&format!("{} ", attr.to_token_stream()));
+        } else if attr.path.is_ident(ATTRS_CFG_ATTR) {
+            if let Ok(Meta::List(list)) = attr.parse_meta() {
+                propagate_cfg_attr(&list, &mut propagated);
+            }
+        }
+    }
+    propagated
+}
+
+/// Adds the `#[cfg]` and `#[deprecated]` attributes inside of
+/// a `#[cfg_attr]`, which may be nested, to the propagated ones.
+fn propagate_cfg_attr(list: &MetaList, propagated: &mut PropagatedAttrs) {
+    let mut nested = list.nested.iter();
+    let predicate = match nested.next() {
+        Some(predicate) => predicate.to_token_stream(),
+        None => return,
+    };
+    for meta in nested {
+        match meta {
+            NestedMeta::Meta(Meta::List(inner)) if inner.path.is_ident(ATTRS_CFG) => {
+                propagated.cfg.push_str(&format!(
+                    "#[cfg(any(not({}), all({})))] ",
+                    predicate,
+                    inner.nested.to_token_stream()
+                ));
+            }
+            NestedMeta::Meta(Meta::List(inner)) if inner.path.is_ident(ATTRS_CFG_ATTR) => {
+                // The inner predicate only applies if the outer one holds.
+                let mut nested_inner = inner.nested.clone();
+                if let Some(first) = nested_inner.first_mut() {
+                    *first = syn::parse_quote!(all(#predicate, #first));
+                }
+                propagate_cfg_attr(
+                    &MetaList {
+                        nested: nested_inner,
+                        ..inner.clone()
+                    },
+                    propagated,
+                );
+            }
+            NestedMeta::Meta(meta) if meta.path().is_ident(ATTRS_DEPRECATED) => {
+                propagated.deprecated.push_str(&format!(
+                    "#[cfg_attr({}, {})] ",
+                    predicate,
+                    meta.to_token_stream()
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The predicates of `#[cfg]` attributes, as fetched by
+/// [`fetch_propagated_attrs`], which all hold exactly when
+/// the attributes do. Predicates combined with `all` are split.
+fn cfg_predicates(cfg: &str) -> Vec<NestedMeta> {
+    let attrs = Attribute::parse_outer
+        .parse_str(cfg)
+        .expect("Failed to parse the #[cfg] attributes");
+    let mut predicates = vec![];
+    for attr in attrs.iter() {
+        match attr.parse_meta() {
+            Ok(Meta::List(list)) => predicates.extend(list.nested),
+            _ => panic!("Malformed attribute. Found '{}'", attr.to_token_stream()),
+        }
+    }
+    while let Some(ix) = predicates.iter().position(|predicate| {
+        matches!(predicate, NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("all"))
+    }) {
+        if let NestedMeta::Meta(Meta::List(list)) = predicates.remove(ix) {
+            predicates.extend(list.nested);
         }
     }
+    predicates
+}
+
+/// Turns `#[cfg]` attributes, as fetched by [`fetch_propagated_attrs`],
+/// into a single `#[cfg]` attribute that holds exactly when
+/// the given ones do not.
+pub(crate) fn negate_cfg(cfg: &str) -> String {
+    let predicates: Vec<String> = cfg_predicates(cfg)
+        .iter()
+        .map(|predicate| predicate.to_token_stream().to_string())
+        .collect();
+    format!("#[cfg(not(all({})))] ", predicates.join(", "))
+}
+
+/// Checks if two sets of `#[cfg]` attributes, as fetched by
+/// [`fetch_propagated_attrs`], can never hold at the same time.
+///
+/// This is a syntactic check, which only detects a predicate
+/// of one set that is the negation of a predicate of the other,
+/// such as `#[cfg(unix)]` and `#[cfg(not(unix))]`.
+pub(crate) fn cfgs_exclusive(cfg_1: &str, cfg_2: &str) -> bool {
+    if cfg_1.is_empty() || cfg_2.is_empty() {
+        return false;
+    }
+    let negates = |predicate: &NestedMeta, other: &NestedMeta| match predicate {
+        NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("not") => {
+            list.nested.len() == 1
+                && list.nested[0].to_token_stream().to_string()
+                    == other.to_token_stream().to_string()
+        }
+        _ => false,
+    };
+    let predicates_1 = cfg_predicates(cfg_1);
+    let predicates_2 = cfg_predicates(cfg_2);
+    predicates_1.iter().any(|p_1| {
+        predicates_2
+            .iter()
+            .any(|p_2| negates(p_1, p_2) || negates(p_2, p_1))
+    })
 }
 
 /// The input to the `EnumConversion` macro
@@ -34,9 +270,13 @@ impl From<&str> for VariantInfo {
 /// `TryTo`/ `TryFrom` traits. In that case,
 /// custom error types and a closure for converting
 /// to that error type must be given.
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
 pub enum ErrorConfig {
-    Custom { error_ty: String, map_err: String },
+    Custom {
+        error_ty: String,
+        map_err: String,
+    },
+    #[default]
     Default,
 }
 
@@ -61,27 +301,22 @@ enum ErrorConfigParam {
     MapErr(String),
 }
 
-impl Default for ErrorConfig {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 /// Parse attribute macros on the enum and variants.
 ///
 /// Once the attribute macros are processed, they
 /// are removed from the AST.
 ///
-/// Returns `true` iff the attribute is `[DeriveTryFrom]`.
-pub(crate) fn parse_attrs(attrs: &mut Vec<Attribute>) -> bool {
-    let mut derive_try_from = false;
+/// Returns how the `TryFrom` traits should be derived
+/// according to the `[DeriveTryFrom]` attribute.
+pub(crate) fn parse_attrs(attrs: &mut Vec<Attribute>) -> TryFromConfig {
+    let mut derive_try_from = TryFromConfig::Off;
     *attrs = attrs
         .clone()
         .into_iter()
         .filter(|attr| {
             if let Some(prefix) = attr.path.segments.first().map(|seg| seg.ident.to_string()) {
                 if prefix == ATTR_TRY_FROM {
-                    derive_try_from = true;
+                    derive_try_from = parse_try_from_args(attr);
                     false
                 } else {
                     true
@@ -94,6 +329,148 @@ pub(crate) fn parse_attrs(attrs: &mut Vec<Attribute>) -> bool {
     derive_try_from
 }
 
+/// The optional items to generate for the enum, as
+/// requested by attributes on the enum.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub(crate) struct EnumOptions {
+    /// `#[DeriveKind]`: generate a fieldless enum
+    /// with a unit variant for each variant.
+    pub kind: bool,
+    /// `#[DeriveVisitor]`: generate visitor traits with
+    /// a method for each variant.
+    pub visitor: bool,
+    /// `#[DerivePin]`: generate conversions from pinned
+    /// references to the enum to pinned references to
+    /// the values of its variants.
+    pub pin: bool,
+    /// `#[DeriveViews]`: generate views of the enum holding
+    /// references to the values of its variants.
+    pub views: bool,
+    /// `#[DeriveBuckets]`: generate a collection of the enum
+    /// storing the values of each variant separately.
+    pub buckets: bool,
+    /// `#[DeriveVariantSet]`: generate a set of variants
+    /// stored as bits. Implies `#[DeriveKind]`.
+    pub set: bool,
+}
+
+/// Parse the attributes on the enum that request optional
+/// items. These attributes are removed from the AST.
+pub(crate) fn parse_enum_options(attrs: &mut Vec<Attribute>) -> EnumOptions {
+    let mut options = EnumOptions::default();
+    attrs.retain(|attr| {
+        let option = if attr.path.is_ident(ATTR_KIND) {
+            &mut options.kind
+        } else if attr.path.is_ident(ATTR_VISITOR) {
+            &mut options.visitor
+        } else if attr.path.is_ident(ATTR_PIN) {
+            &mut options.pin
+        } else if attr.path.is_ident(ATTR_VIEWS) {
+            &mut options.views
+        } else if attr.path.is_ident(ATTR_BUCKETS) {
+            &mut options.buckets
+        } else if attr.path.is_ident(ATTR_SET) {
+            &mut options.set
+        } else {
+            return true;
+        };
+        if !attr.tokens.is_empty() {
+            panic!(
+                "{} expects no arguments. Found '{}'",
+                attr.path.to_token_stream(),
+                attr.tokens
+            );
+        }
+        *option = true;
+        false
+    });
+    options
+}
+
+/// Parse the `#[Transparent]` attribute of a variant,
+/// removing it from the AST.
+pub(crate) fn parse_transparent(attrs: &mut Vec<Attribute>) -> bool {
+    let mut transparent = false;
+    attrs.retain(|attr| {
+        if !attr.path.is_ident(ATTR_TRANSPARENT) {
+            return true;
+        }
+        if !attr.tokens.is_empty() {
+            panic!(
+                "{} expects no arguments. Found '{}'",
+                ATTR_TRANSPARENT, attr.tokens
+            );
+        }
+        transparent = true;
+        false
+    });
+    transparent
+}
+
+/// Parse the `#[Transitions(Old => New, ..)]` attribute of the
+/// enum, removing it from the AST. Returns the pairs of types
+/// the variants may transition between, or `None` if the
+/// attribute is absent and all transitions are allowed.
+pub(crate) fn parse_transitions(attrs: &mut Vec<Attribute>) -> Option<Vec<(String, String)>> {
+    let mut transitions = None;
+    attrs.retain(|attr| {
+        if !attr.path.is_ident(ATTR_TRANSITIONS) {
+            return true;
+        }
+        if transitions.is_some() {
+            panic!("{} may only be given once", ATTR_TRANSITIONS);
+        }
+        let parser = |input: ParseStream| {
+            Punctuated::<(Type, Type), Token![,]>::parse_terminated_with(input, parse_transition)
+        };
+        let pairs = attr.parse_args_with(parser).unwrap_or_else(|_| {
+            panic!(
+                "{} expects arguments of the form 'Old => New'. Found '{}'",
+                ATTR_TRANSITIONS, attr.tokens
+            )
+        });
+        transitions = Some(
+            pairs
+                .iter()
+                .map(|(old, new)| {
+                    (
+                        old.to_token_stream().to_string(),
+                        new.to_token_stream().to_string(),
+                    )
+                })
+                .collect(),
+        );
+        false
+    });
+    transitions
+}
+
+/// Parse a single transition of the form `Old => New`.
+fn parse_transition(input: ParseStream) -> syn::Result<(Type, Type)> {
+    let old = input.parse()?;
+    input.parse::<Token![=>]>()?;
+    Ok((old, input.parse()?))
+}
+
+/// The `[DeriveTryFrom]` attribute takes either no
+/// arguments or the single argument `auto`.
+fn parse_try_from_args(attr: &Attribute) -> TryFromConfig {
+    if attr.tokens.is_empty() {
+        return TryFromConfig::On;
+    }
+    if let Ok(Meta::List(list)) = attr.parse_meta() {
+        if let Some(NestedMeta::Meta(Meta::Path(path))) = list.nested.first() {
+            if list.nested.len() == 1 && path.is_ident(ARG_AUTO) {
+                return TryFromConfig::Auto;
+            }
+        }
+    }
+    panic!(
+        "DeriveTryFrom expects either no arguments or `auto`. Found '{}'",
+        attr.tokens,
+    )
+}
+
 /// Process the arguments passed into the attribute.
 /// Panics if they are not of the right format or
 /// the wrong number of arguments were passed in.
@@ -192,10 +569,105 @@ mod test_attrs {
         .expect("Test failed");
         let ast_clone = ast.clone();
         let attrs = parse_attrs(&mut ast.attrs);
-        assert!(!attrs);
+        assert_eq!(attrs, TryFromConfig::Off);
         assert_eq!(ast, ast_clone);
     }
 
+    /// Test that `#[cfg]` and `#[deprecated]` attributes are
+    /// found and combined with those of the enum.
+    #[test]
+    fn test_propagated_attrs() {
+        let ast: DeriveInput = parse_str(
+            r#"
+            #[cfg(unix)]
+            #[derive(Debug)]
+            enum Enum {
+                #[cfg(feature = "tls")]
+                #[deprecated(note = "Use F2")]
+                F1(i64),
+                F2(bool),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let global = fetch_propagated_attrs(&ast.attrs);
+        assert_eq!(
+            global,
+            PropagatedAttrs {
+                cfg: "# [cfg (unix)] ".to_string(),
+                deprecated: "".to_string(),
+            }
+        );
+        let variants = match ast.data {
+            syn::Data::Enum(data) => data.variants,
+            _ => panic!("Test failed"),
+        };
+        let local = fetch_propagated_attrs(&variants[0].attrs);
+        assert_eq!(
+            local.deprecated,
+            "# [deprecated (note = \"Use F2\")] ".to_string()
+        );
+        assert_eq!(
+            global.for_variant(&local),
+            "# [cfg (unix)] # [cfg (feature = \"tls\")] #[allow(deprecated)] "
+        );
+        let local = fetch_propagated_attrs(&variants[1].attrs);
+        assert_eq!(global.for_variant(&local), "# [cfg (unix)] ");
+    }
+
+    /// Test that `#[cfg]` attributes are combined into
+    /// their negation.
+    #[test]
+    fn test_negate_cfg() {
+        assert_eq!(
+            negate_cfg("# [cfg (unix)] # [cfg (feature = \"tls\")] "),
+            "#[cfg(not(all(unix, feature = \"tls\")))] "
+        );
+    }
+
+    /// Test that `#[cfg]` and `#[deprecated]` attributes inside of
+    /// `#[cfg_attr]` are found.
+    #[test]
+    fn test_propagated_cfg_attr() {
+        let ast: DeriveInput = parse_str(
+            r#"
+            #[cfg_attr(test, cfg(unix), derive(Debug))]
+            #[cfg_attr(feature = "a", cfg_attr(feature = "b", cfg(windows)))]
+            #[cfg_attr(feature = "old", deprecated)]
+            enum Enum {
+                F1(i64),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        assert_eq!(
+            fetch_propagated_attrs(&ast.attrs),
+            PropagatedAttrs {
+                cfg: "#[cfg(any(not(test), all(unix)))] \
+                      #[cfg(any(not(all (feature = \"a\" , feature = \"b\")), all(windows)))] "
+                    .to_string(),
+                deprecated: "#[cfg_attr(feature = \"old\", deprecated)] ".to_string(),
+            }
+        );
+    }
+
+    /// Test that `#[cfg]` attributes negating each
+    /// other are found to be exclusive.
+    #[test]
+    fn test_cfgs_exclusive() {
+        assert!(cfgs_exclusive("# [cfg (unix)] ", "# [cfg (not (unix))] "));
+        assert!(cfgs_exclusive(
+            "# [cfg (all (test , not (feature = \"a\")))] ",
+            "# [cfg (feature = \"a\")] "
+        ));
+        assert!(!cfgs_exclusive("# [cfg (unix)] ", "# [cfg (windows)] "));
+        assert!(!cfgs_exclusive("# [cfg (unix)] ", ""));
+        assert!(!cfgs_exclusive(
+            "# [cfg (any (unix , test))] ",
+            "# [cfg (not (unix))] "
+        ));
+    }
+
     /// Test that the top level macros are stripped when they
     /// are processed.
     #[test]
@@ -212,7 +684,7 @@ mod test_attrs {
         "#,
         )
         .expect("Test failed.");
-        assert!(parse_attrs(&mut ast.attrs));
+        assert_eq!(parse_attrs(&mut ast.attrs), TryFromConfig::On);
         let expected: DeriveInput = parse_str(
             r#"
             #[EnumConversion]
@@ -227,6 +699,127 @@ mod test_attrs {
         assert_eq!(ast, expected);
     }
 
+    /// Test that the attributes requesting optional
+    /// items are stripped.
+    #[test]
+    fn test_enum_options() {
+        let mut ast: DeriveInput = parse_str(
+            r#"
+            #[derive(Debug)]
+            #[DeriveKind]
+            #[DeriveVisitor]
+            enum Enum {
+                F1(i64),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        assert_eq!(
+            parse_enum_options(&mut ast.attrs),
+            EnumOptions {
+                kind: true,
+                visitor: true,
+                pin: false,
+                views: false,
+                buckets: false,
+                set: false,
+            }
+        );
+        assert_eq!(ast.attrs.len(), 1);
+        assert_eq!(parse_enum_options(&mut ast.attrs), EnumOptions::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "DeriveKind expects no arguments. Found '(all)'")]
+    fn test_enum_options_args() {
+        let mut ast: DeriveInput = parse_str(
+            r#"
+            #[DeriveKind(all)]
+            enum Enum {
+                F1(i64),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        parse_enum_options(&mut ast.attrs);
+    }
+
+    /// Test that the declared transitions are parsed and
+    /// the attribute is stripped.
+    #[test]
+    fn test_transitions() {
+        let mut ast: DeriveInput = parse_str(
+            r#"
+            #[derive(Debug)]
+            #[Transitions(Closed => Vec<u8>, Vec<u8> => Closed,)]
+            enum Enum {
+                F1(Closed),
+                F2(Vec<u8>),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        assert_eq!(
+            parse_transitions(&mut ast.attrs),
+            Some(vec![
+                ("Closed".to_string(), "Vec < u8 >".to_string()),
+                ("Vec < u8 >".to_string(), "Closed".to_string()),
+            ])
+        );
+        assert_eq!(ast.attrs.len(), 1);
+        assert_eq!(parse_transitions(&mut ast.attrs), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transitions expects arguments of the form 'Old => New'")]
+    fn test_transitions_args() {
+        let mut ast: DeriveInput = parse_str(
+            r#"
+            #[Transitions(Closed, Open)]
+            enum Enum {
+                F1(Closed),
+                F2(Open),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        parse_transitions(&mut ast.attrs);
+    }
+
+    /// Test that `auto` is accepted as an argument to
+    /// `DeriveTryFrom` and the attribute is still stripped.
+    #[test]
+    fn test_try_from_auto() {
+        let mut ast: DeriveInput = parse_str(
+            r#"
+            #[DeriveTryFrom(auto)]
+            enum Enum {
+                F1(i64),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        assert_eq!(parse_attrs(&mut ast.attrs), TryFromConfig::Auto);
+        assert!(ast.attrs.is_empty());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "DeriveTryFrom expects either no arguments or `auto`. Found '(always)'"
+    )]
+    fn test_try_from_bad_arg() {
+        let mut ast: DeriveInput = parse_str(
+            r#"
+            #[DeriveTryFrom(always)]
+            enum Enum {
+                F1(i64),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        _ = parse_attrs(&mut ast.attrs);
+    }
+
     /// Test that providing no arguments to
     /// `EnumConversion` returns the default
     /// error config.
@@ -1,12 +1,28 @@
-use std::collections::HashMap;
 use std::fmt::Write as _;
 
 use quote::ToTokens;
 use syn::__private::Span;
-use syn::{Data, GenericParam, Lifetime, LifetimeDef, Token};
+use syn::visit_mut::{self, VisitMut};
+use syn::{Data, GenericParam, Generics, Lifetime, LifetimeDef, Token, Type};
 
 use super::*;
-use crate::parse_attributes::{parse_attrs, VariantInfo};
+use crate::parse_attributes::{
+    cfgs_exclusive, fetch_propagated_attrs, parse_attrs, parse_transparent, Transparent,
+    TryFromConfig, VariantInfo, Wrapper,
+};
+use crate::unify::{bounded_params, check_collisions};
+
+/// Types that are `#[fundamental]` do not cover
+/// their type parameters for the purposes of the
+/// orphan rules.
+const FUNDAMENTAL_TYPES: [&str; 2] = ["Box", "Pin"];
+
+/// Types `W` of the standard library implementing `From<T> for W<T>`,
+/// which gives them `TryFrom<T>` through the blanket implementation
+/// for types implementing `Into`.
+const FROM_WRAPPERS: [&str; 9] = [
+    "Box", "Rc", "Arc", "Option", "Cell", "RefCell", "Mutex", "RwLock", "Poll",
+];
 
 /// This functions determines the name of the enum with generic
 /// params attached.
@@ -19,7 +35,10 @@ use crate::parse_attributes::{parse_attrs, VariantInfo};
 ///     F2([T; X])
 /// }
 /// ```
-/// This function should return `(Enum<'a, T, X>, vec!['a])`
+/// This function should return `(Enum<'a, T, { X }>, vec!['a])`
+///
+/// Const parameters are passed as braced arguments, so that they
+/// are not mistaken for a type of the same name.
 pub fn fetch_name_with_generic_params(ast: &DeriveInput) -> (String, Vec<String>) {
     let mut param_string = String::new();
     let mut lifetimes = vec![];
@@ -31,7 +50,10 @@ pub fn fetch_name_with_generic_params(ast: &DeriveInput) -> (String, Vec<String>
                 lifetimes.push(lifetime.to_string());
                 lifetime
             }
-            syn::GenericParam::Const(constant) => constant.ident.to_token_stream(),
+            syn::GenericParam::Const(constant) => {
+                let ident = &constant.ident;
+                quote::quote!({ #ident })
+            }
         };
         _ = write!(param_string, "{},", next);
     }
@@ -84,17 +106,27 @@ pub struct ImplGenerics {
 ///
 /// For traits the return references, the lifetime of the reference must be bound
 /// by lifetimes in the definition of the enum.
+///
+/// Default values of generic parameters are removed, as they are not
+/// allowed in impl headers.
 pub fn fetch_impl_generics(ast: &DeriveInput, lifetime: &str, bounds: &[String]) -> ImplGenerics {
     let mut generics = ast.generics.clone();
+    strip_defaults(&mut generics);
     let mut generics_ref = generics.clone();
     generics_ref
         .params
         .push(GenericParam::Lifetime(bound_lifetime(lifetime, bounds)));
 
+    // Further predicates are appended to the where clause, so
+    // trailing punctuation and empty where clauses are removed.
     let where_clause = generics
         .where_clause
         .take()
-        .map(|w| w.to_token_stream().to_string());
+        .filter(|w| !w.predicates.is_empty())
+        .map(|mut w| {
+            w.predicates = w.predicates.into_iter().collect();
+            w.to_token_stream().to_string()
+        });
     ImplGenerics {
         impl_generics: generics.to_token_stream().to_string(),
         impl_generics_ref: generics_ref.to_token_stream().to_string(),
@@ -102,6 +134,25 @@ pub fn fetch_impl_generics(ast: &DeriveInput, lifetime: &str, bounds: &[String])
     }
 }
 
+/// Removes the default values of type and const generic
+/// parameters, e.g. `<T = String, const N: usize = 4>` becomes
+/// `<T, const N: usize>`.
+fn strip_defaults(generics: &mut Generics) {
+    for param in generics.params.iter_mut() {
+        match param {
+            GenericParam::Type(type_) => {
+                type_.eq_token = None;
+                type_.default = None;
+            }
+            GenericParam::Const(constant) => {
+                constant.eq_token = None;
+                constant.default = None;
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+}
+
 /// Given a lifetime and a list of other lifetimes, creates
 /// the bound that states the input lifetime cannot outlive
 /// the lifetimes in the list.
@@ -119,24 +170,44 @@ pub fn bound_lifetime(lifetime: &str, bounds: &[String]) -> syn::LifetimeDef {
     lifetime_def
 }
 
-/// Fetches the name of each variant in the enum and
-/// maps it to a string representation of its type.
+/// Fetches the name of each variant in the enum along
+/// with a string representation of its type. The variants
+/// are returned in the order they are declared, so that the
+/// generated code does not change between builds.
 ///
 /// Also performs validation for unsupported enum types.
 /// These include:
 ///  * Enums with multiple variants of the same type.
 ///  * Enums with variants with multiple or named fields.
 ///  * Enums with unit variants.
+///  * Enums with variants whose types are equal for some
+///    choice of generic parameters.
 ///
 /// Will panic if the input type is not an enum.
-pub(crate) fn fetch_fields_from_enum(ast: &mut DeriveInput) -> HashMap<String, VariantInfo> {
+pub(crate) fn fetch_fields_from_enum(ast: &mut DeriveInput) -> Vec<VariantInfo> {
     let derive_globally = parse_attrs(&mut ast.attrs);
+    let enum_attrs = fetch_propagated_attrs(&ast.attrs);
+    let type_params = ast
+        .generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect::<Vec<String>>();
+    let (fullname, _) = fetch_name_with_generic_params(ast);
+    let mut replace_self = ReplaceSelf(syn::parse_str(&fullname).unwrap());
+    let mut vars = type_params.clone();
+    vars.extend(
+        ast.generics
+            .const_params()
+            .map(|param| param.ident.to_string()),
+    );
+    let bounded = bounded_params(&ast.generics, &type_params);
     if let Data::Enum(data) = &mut ast.data {
-        let mut num_fields: usize = 0;
-        let mut types = data
+        let mut variant_types = vec![];
+        let variants = data
             .variants
             .iter_mut()
-            .map(|var| match &var.fields {
+            .enumerate()
+            .map(|(index, var)| match &var.fields {
                 syn::Fields::Unnamed(field_) => {
                     if field_.unnamed.len() != 1 {
                         panic!(
@@ -144,21 +215,50 @@ pub(crate) fn fetch_fields_from_enum(ast: &mut DeriveInput) -> HashMap<String, V
                              not contain multiple fields."
                         );
                     }
-                    let var_ty = field_
-                        .unnamed
-                        .iter()
-                        .next()
-                        .unwrap()
-                        .ty
-                        .to_token_stream()
-                        .to_string();
+                    let mut ty = field_.unnamed.iter().next().unwrap().ty.clone();
+                    replace_self.visit_type_mut(&mut ty);
+                    let ty = &ty;
                     let var_name = var.ident.to_token_stream().to_string();
+                    let config = parse_attrs(&mut var.attrs).or(derive_globally);
+                    let transparent = parse_transparent(&mut var.attrs).then(|| {
+                        let (wrapper, inner) = fetch_wrapper(ty).unwrap_or_else(|| {
+                            panic!(
+                                "The `Transparent` attribute can only be placed on variants \
+                                 holding a `Box`, `Rc` or `Arc`. Found `{}` in variant `{}`.",
+                                ty.to_token_stream(),
+                                var_name,
+                            )
+                        });
+                        // The conversions to and from the inner type must
+                        // not conflict with those of the other variants.
+                        variant_types.push((
+                            var_name.clone(),
+                            inner.clone(),
+                            fetch_propagated_attrs(&var.attrs).cfg,
+                        ));
+                        Transparent {
+                            wrapper,
+                            inner: inner.to_token_stream().to_string(),
+                            is_unsized: is_unsized(&inner),
+                        }
+                    });
+                    let var_attrs = fetch_propagated_attrs(&var.attrs);
                     let var_info = VariantInfo {
-                        ty: var_ty,
-                        try_from: parse_attrs(&mut var.attrs) || derive_globally,
+                        try_from: derive_try_from(
+                            config,
+                            &var_name,
+                            ty,
+                            &replace_self.0,
+                            &type_params,
+                        ),
+                        attrs: enum_attrs.for_variant(&var_attrs),
+                        deprecated: var_attrs.deprecated,
+                        cfg: var_attrs.cfg,
+                        transparent,
+                        ..VariantInfo::new(index, &var_name, &ty.to_token_stream().to_string())
                     };
-                    num_fields += 1;
-                    (var_info, var_name)
+                    variant_types.push((var_name, ty.clone(), var_info.cfg.clone()));
+                    var_info
                 }
                 syn::Fields::Named(_) => {
                     panic!("Can only derive for enums whose types do not have named fields.")
@@ -167,41 +267,370 @@ pub(crate) fn fetch_fields_from_enum(ast: &mut DeriveInput) -> HashMap<String, V
                     panic!("Can only derive for enums who don't contain unit types as variants.")
                 }
             })
-            .collect::<HashMap<VariantInfo, String>>();
-        let types: HashMap<String, VariantInfo> = types.drain().map(|(k, v)| (v, k)).collect();
-        if types.keys().len() != num_fields {
-            panic!("Cannot derive for enums with more than one field with the same type.")
+            .collect::<Vec<VariantInfo>>();
+        // Variants that never exist together may hold the same type.
+        for (ix, info) in variants.iter().enumerate() {
+            if variants[ix + 1..]
+                .iter()
+                .any(|other| other.ty == info.ty && !cfgs_exclusive(&info.cfg, &other.cfg))
+            {
+                panic!("Cannot derive for enums with more than one field with the same type.")
+            }
         }
-        types
+        check_collisions(&ast.ident.to_string(), &variant_types, &vars, &bounded);
+        variants
     } else {
         panic!("Can only derive for enums.")
     }
 }
 
+/// Replaces `Self` in the type of a variant with the enum's
+/// type (including generic params). Outside of the enum
+/// definition, for example in the generated `From` impls,
+/// `Self` would refer to a different type.
+struct ReplaceSelf(Type);
+
+impl VisitMut for ReplaceSelf {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            if path.qself.is_none() && path.path.is_ident("Self") {
+                *ty = self.0.clone();
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+/// Decides if the `TryFrom` traits should be derived for a variant.
+///
+/// In `auto` mode, variants whose types would break the orphan
+/// rules or already implement `TryFrom<Enum>` are skipped. If the
+/// traits were explicitly requested for such a variant, we panic
+/// with an explanation rather than leaving the user with a less
+/// helpful compiler error.
+fn derive_try_from(
+    config: TryFromConfig,
+    var_name: &str,
+    ty: &Type,
+    enum_ty: &Type,
+    params: &[String],
+) -> bool {
+    match config {
+        TryFromConfig::Off => false,
+        TryFromConfig::Auto => is_orphan_safe(ty, params) && !wraps_enum(ty, enum_ty),
+        TryFromConfig::On => {
+            if !is_orphan_safe(ty, params) {
+                panic!(
+                    "Cannot derive TryFrom for variant `{}` as its type `{}` is an uncovered \
+                     generic parameter (see error E0210). Use the `TryTo` trait for this variant \
+                     instead, or `#[DeriveTryFrom(auto)]` to skip such variants.",
+                    var_name,
+                    ty.to_token_stream(),
+                );
+            }
+            if wraps_enum(ty, enum_ty) {
+                panic!(
+                    "Cannot derive TryFrom for variant `{}` as its type `{}` implements \
+                     `From` for the enum, so the standard library already implements \
+                     `TryFrom` for it (see error E0119). Use the `TryTo` trait for this \
+                     variant instead, or `#[DeriveTryFrom(auto)]` to skip such variants.",
+                    var_name,
+                    ty.to_token_stream(),
+                );
+            }
+            true
+        }
+    }
+}
+
+/// Checks if the type is one of the [`FROM_WRAPPERS`] around the
+/// enum itself, e.g. `Box<Self>`. Such types implement `From<Enum>`,
+/// so deriving `TryFrom<Enum>` for them conflicts with the blanket
+/// implementation of the standard library.
+fn wraps_enum(ty: &Type, enum_ty: &Type) -> bool {
+    let path = match ty {
+        Type::Paren(paren) => return wraps_enum(&paren.elem, enum_ty),
+        Type::Group(group) => return wraps_enum(&group.elem, enum_ty),
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return false,
+    };
+    let last = path.segments.last().unwrap();
+    if !FROM_WRAPPERS.contains(&last.ident.to_string().as_str()) {
+        return false;
+    }
+    match &last.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => {
+                    inner.to_token_stream().to_string() == enum_ty.to_token_stream().to_string()
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// If the type is a `Box`, `Rc` or `Arc`, returns which
+/// along with the type it points to.
+fn fetch_wrapper(ty: &Type) -> Option<(Wrapper, Type)> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segments = path
+        .segments
+        .iter()
+        .map(|seg| seg.ident.to_string())
+        .collect::<Vec<String>>();
+    let wrapper = match segments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        ["Box"] | ["std" | "alloc", "boxed", "Box"] => Wrapper::Box,
+        ["Rc"] | ["std" | "alloc", "rc", "Rc"] => Wrapper::Rc,
+        ["Arc"] | ["std" | "alloc", "sync", "Arc"] => Wrapper::Arc,
+        _ => return None,
+    };
+    match &path.segments.last().unwrap().arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => Some((wrapper, inner.clone())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Checks for the types that are unsized regardless of
+/// the generic parameters: `str`, slices and trait objects.
+fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Paren(paren) => is_unsized(&paren.elem),
+        Type::Group(group) => is_unsized(&group.elem),
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("str"),
+        _ => false,
+    }
+}
+
+/// Determines whether `TryFrom<Enum> for Type` can be implemented
+/// without breaking the orphan rules. This is not the case if
+/// `Type` is one of the enum's generic parameters, possibly behind
+/// references or other fundamental types.
+///
+/// # Example
+/// ```
+/// enum Enum<'a, U> {
+///     F1(&'a U),
+///     F2(Box<U>),
+///     F3(Vec<U>),
+/// }
+/// ```
+/// Only `F3` is orphan safe.
+fn is_orphan_safe(ty: &Type, params: &[String]) -> bool {
+    match ty {
+        Type::Reference(reference) => is_orphan_safe(&reference.elem, params),
+        Type::Paren(paren) => is_orphan_safe(&paren.elem, params),
+        Type::Group(group) => is_orphan_safe(&group.elem, params),
+        Type::Path(path) if path.qself.is_none() => {
+            if let Some(ident) = path.path.get_ident() {
+                return !params.contains(&ident.to_string());
+            }
+            let last = path.path.segments.last().unwrap();
+            if !FUNDAMENTAL_TYPES.contains(&last.ident.to_string().as_str()) {
+                return true;
+            }
+            match &last.arguments {
+                syn::PathArguments::AngleBracketed(args) => args.args.iter().all(|arg| match arg {
+                    syn::GenericArgument::Type(inner) => is_orphan_safe(inner, params),
+                    _ => true,
+                }),
+                _ => true,
+            }
+        }
+        _ => true,
+    }
+}
+
 /// Creates a marker enum for each field in the enum
 /// under a new module.
 ///
 /// Used to identify types in the enum and disambiguate
 /// generic parameters.
-pub(crate) fn create_marker_enums(name: &str, types: &HashMap<String, VariantInfo>) -> String {
+///
+/// The module has the same visibility as the enum, so that
+/// the markers can be named wherever the enum can, e.g. in
+/// `GetVariant` bounds of downstream crates.
+///
+/// The `#[cfg]` attributes of the enum are placed on the
+/// module and those of the variants on the markers.
+///
+/// The module also holds the given aliases, as made by
+/// `impl_bound_aliases`, which see the items of the module
+/// the enum is in.
+pub(crate) fn create_marker_enums(
+    name: &str,
+    vis: &str,
+    cfg: &str,
+    variants: &[VariantInfo],
+    aliases: &str,
+) -> String {
+    let vis = if vis.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", vis)
+    };
     let mut piece = format!(
-        "#[allow(non_snake_case)]\n mod enum___conversion___{}",
-        name
+        "{}#[allow(non_snake_case)]\n #[doc = \"Marker types for the variants of `{}`.\"]\n \
+         {}mod enum___conversion___{}",
+        cfg, name, vis, name
     );
     piece.push_str("{ ");
-    for field in types.keys() {
-        _ = write!(piece, "pub(crate) enum {}{{}}", field);
+    if !aliases.is_empty() {
+        // The aliases name the types of the variants.
+        piece.push_str("#[allow(unused_imports)] use super::*; ");
+        piece.push_str(aliases);
+    }
+    for info in variants.iter() {
+        _ = write!(
+            piece,
+            "{}{}#[doc = \"Marker type for the variant `{}::{}`.\"] pub enum {}{{}}",
+            info.attrs, info.deprecated, name, info.name, info.name
+        );
     }
     piece.push('}');
     piece
 }
 
+/// Creates the definition of a view of the enum, whose variants
+/// hold references to the values of the enum's variants. The view
+/// takes the lifetime of the references as its first parameter.
+///
+/// # Example
+/// For `reference = "&'enum_view mut"`,
+/// ```
+/// enum Enum<T: Clone> {
+///     F1(T),
+///     F2(bool),
+/// }
+/// ```
+/// gives
+/// ```
+/// enum EnumMut<'enum_view, T: Clone> {
+///     F1(&'enum_view mut T),
+///     F2(&'enum_view mut bool),
+/// }
+/// ```
+pub(crate) fn create_view_enum(
+    ast: &DeriveInput,
+    view: &str,
+    lifetime: &str,
+    reference: &str,
+    cfg: &str,
+    variants: &[VariantInfo],
+) -> String {
+    let mut generics = ast.generics.clone();
+    let where_clause = generics
+        .where_clause
+        .take()
+        .map(|w| w.to_token_stream().to_string())
+        .unwrap_or_default();
+    generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(Lifetime::new(lifetime, Span::call_site()))),
+    );
+    let mut piece = format!(
+        "{}#[doc = \"A view of [`{}`] borrowing the value of its active variant.\"] {} enum {}{} {} {{ ",
+        cfg,
+        ast.ident,
+        ast.vis.to_token_stream(),
+        view,
+        generics.to_token_stream(),
+        where_clause,
+    );
+    for info in variants.iter() {
+        _ = write!(
+            piece,
+            "{}{}{}({} {}), ",
+            info.cfg, info.deprecated, info.name, reference, info.ty
+        );
+    }
+    piece.push('}');
+    piece
+}
+
+/// Makes the string representation of a type more readable
+/// by removing the spaces between tokens where they would
+/// usually not be written.
+///
+/// # Example
+/// `"Box < & 'a dyn Into < T > >"` becomes `"Box<&'a dyn Into<T>>"`.
+pub(crate) fn pretty_type(ty: &str) -> String {
+    const NO_SPACE_AFTER: [&str; 7] = ["<", "(", "[", "&", "::", "*", "!"];
+    const NO_SPACE_BEFORE: [&str; 9] = ["<", ">", "(", ")", "]", ",", ";", "::", "!"];
+    let mut pretty = String::new();
+    let mut previous = "";
+    for token in ty.split_whitespace() {
+        // Groups such as `(& 'a usize)` are not split by whitespace
+        // at their delimiters, so we check prefixes and suffixes.
+        let after = previous != "->" && NO_SPACE_AFTER.iter().any(|p| previous.ends_with(p));
+        let before = token != "->" && NO_SPACE_BEFORE.iter().any(|p| token.starts_with(p));
+        if !pretty.is_empty() && !after && !before {
+            pretty.push(' ');
+        }
+        pretty.push_str(token);
+        previous = token;
+    }
+    pretty
+}
+
 /// Get the fully qualified name of the marker struct
 /// associated with an enum variant.
 pub fn get_marker(name: &str, field: &str) -> String {
     format!("enum___conversion___{}::{}", name, field)
 }
 
+/// Converts the name of a variant into snake case for use
+/// in method names, e.g. `HttpRequest` and `HTTPRequest`
+/// both become `http_request`.
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<char>>();
+    let mut snake = String::new();
+    for (ix, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && ix > 0 {
+            let prev = chars[ix - 1];
+            let next_lower = chars.get(ix + 1).is_some_and(|n| n.is_lowercase());
+            if prev != '_' && (!prev.is_uppercase() || next_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Get the name of the view of the enum generated by
+/// `#[DeriveViews]` holding shared or mutable references.
+/// Get the name of the alias bounding the type of a variant
+/// with `#[cfg]` attributes, in the module of the markers.
+pub(crate) fn get_bound_alias(field: &str) -> String {
+    format!("bound___{}", field)
+}
+
+pub(crate) fn get_view(name: &str, mutable: bool) -> String {
+    if mutable {
+        format!("{}Mut", name)
+    } else {
+        format!("{}Ref", name)
+    }
+}
+
+/// Get the name of the fieldless enum generated
+/// by `#[DeriveKind]`.
+pub(crate) fn get_kind(name: &str) -> String {
+    format!("{}Kind", name)
+}
+
 #[cfg(test)]
 mod test_parsers {
 
@@ -230,22 +659,49 @@ mod test_parsers {
     fn test_parse_fields_and_types() {
         let mut ast: DeriveInput = syn::parse_str(ENUM).expect("Test failed.");
         let fields = fetch_fields_from_enum(&mut ast);
-        let expected: HashMap<String, VariantInfo> = HashMap::from([
-            ("Array".to_string(), "[u8 ; 20]".into()),
-            ("BareFn".to_string(), "fn (& 'a usize) -> bool".into()),
-            ("Macro".to_string(), "typey ! ()".into()),
-            (
-                "Path".to_string(),
-                "< Vec < & 'a mut T > as IntoIterator > :: Item".into(),
-            ),
-            ("Ptr".to_string(), "* const u8".into()),
-            ("Slice".to_string(), "[u8]".into()),
-            ("Trait".to_string(), "Box < & dyn Into < U > >".into()),
-            ("Tuple".to_string(), "(& 'b i64 , bool)".into()),
-        ]);
+        let expected = vec![
+            VariantInfo::new(0, "Array", "[u8 ; 20]"),
+            VariantInfo::new(1, "BareFn", "fn (& 'a usize) -> bool"),
+            VariantInfo::new(2, "Macro", "typey ! ()"),
+            VariantInfo::new(3, "Path", "< Vec < & 'a mut T > as IntoIterator > :: Item"),
+            VariantInfo::new(4, "Ptr", "* const u8"),
+            VariantInfo::new(5, "Tuple", "(& 'b i64 , bool)"),
+            VariantInfo::new(6, "Slice", "[u8]"),
+            VariantInfo::new(7, "Trait", "Box < & dyn Into < U > >"),
+        ];
         assert_eq!(expected, fields);
     }
 
+    /// Test that `Self` in variant types is replaced
+    /// with the type of the enum.
+    #[test]
+    fn test_replace_self() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Tree<'a, T> {
+                Leaf(&'a T),
+                Node(Box<Self>),
+                List(Vec<Self>),
+                Maybe(Option<Box<Self>>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let expected = vec![
+            VariantInfo::new(0, "Leaf", "& 'a T"),
+            VariantInfo::new(1, "Node", "Box < Tree < 'a , T > >"),
+            VariantInfo::new(2, "List", "Vec < Tree < 'a , T > >"),
+            VariantInfo::new(3, "Maybe", "Option < Box < Tree < 'a , T > > >"),
+        ];
+        assert_eq!(fields, expected);
+        let variant = match &ast.data {
+            Data::Enum(data) => data.variants.iter().nth(1).unwrap(),
+            _ => panic!("Test failed"),
+        };
+        assert_eq!(variant.to_token_stream().to_string(), "Node (Box < Self >)");
+    }
+
     #[test]
     fn test_global_try_from_config() {
         let mut ast: DeriveInput = syn::parse_str(
@@ -259,22 +715,16 @@ mod test_parsers {
         )
         .expect("Test failed");
         let fields = fetch_fields_from_enum(&mut ast);
-        let expected: HashMap<String, VariantInfo> = HashMap::from([
-            (
-                "F1".to_string(),
-                VariantInfo {
-                    ty: "i64".to_string(),
-                    try_from: true,
-                },
-            ),
-            (
-                "F2".to_string(),
-                VariantInfo {
-                    ty: "bool".to_string(),
-                    try_from: true,
-                },
-            ),
-        ]);
+        let expected = vec![
+            VariantInfo {
+                try_from: true,
+                ..VariantInfo::new(0, "F1", "i64")
+            },
+            VariantInfo {
+                try_from: true,
+                ..VariantInfo::new(1, "F2", "bool")
+            },
+        ];
         assert_eq!(fields, expected);
     }
 
@@ -291,19 +741,122 @@ mod test_parsers {
         )
         .expect("Test failed");
         let fields = fetch_fields_from_enum(&mut ast);
-        let expected: HashMap<String, VariantInfo> = HashMap::from([
-            ("F1".to_string(), "i64".into()),
-            (
-                "F2".to_string(),
-                VariantInfo {
-                    ty: "bool".to_string(),
-                    try_from: true,
-                },
-            ),
-        ]);
+        let expected = vec![
+            VariantInfo::new(0, "F1", "i64"),
+            VariantInfo {
+                try_from: true,
+                ..VariantInfo::new(1, "F2", "bool")
+            },
+        ];
         assert_eq!(fields, expected);
     }
 
+    /// Test that in `auto` mode, variants that would break the orphan
+    /// rules do not get `TryFrom` derived.
+    #[test]
+    fn test_try_from_auto_config() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            #[DeriveTryFrom(auto)]
+            enum Enum<'a, U, T> {
+                F1(&'a U),
+                F2(Box<T>),
+                F3(Vec<U>),
+                F4(&'a mut bool),
+                F5(std::pin::Pin<Box<(U, T)>>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let try_from = fields
+            .iter()
+            .map(|info| info.try_from)
+            .collect::<Vec<bool>>();
+        assert_eq!(try_from, vec![false, false, true, true, true]);
+    }
+
+    /// The `auto` mode can also be set on individual variants.
+    #[test]
+    fn test_try_from_auto_local_config() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum<'a, U> {
+                #[DeriveTryFrom(auto)]
+                F1(&'a U),
+                F2(bool),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let fields = fetch_fields_from_enum(&mut ast);
+        assert!(!fields[0].try_from);
+        assert!(!fields[1].try_from);
+    }
+
+    /// Types that implement `From<Enum>`, such as `Box<Self>`, already
+    /// implement `TryFrom<Enum>`, so are skipped in `auto` mode.
+    #[test]
+    fn test_try_from_auto_wrapped_self() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            #[DeriveTryFrom(auto)]
+            enum Tree<T> {
+                Leaf(T),
+                Node(Box<Self>),
+                Shared(std::rc::Rc<Tree<T>>),
+                Maybe(Option<Box<Self>>),
+                List(Vec<Self>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let try_from = fields
+            .iter()
+            .map(|info| info.try_from)
+            .collect::<Vec<bool>>();
+        assert_eq!(try_from, vec![false, false, false, true, true]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot derive TryFrom for variant `Node` as its type `Box < Tree >` \
+                    implements `From` for the enum"
+    )]
+    fn test_try_from_wrapped_self_panics() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            #[DeriveTryFrom]
+            enum Tree {
+                Leaf(i32),
+                Node(Box<Self>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        _ = fetch_fields_from_enum(&mut ast);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot derive TryFrom for variant `F1` as its type `& 'a U` \
+                               is an uncovered generic parameter (see error E0210)."
+    )]
+    fn test_try_from_orphan_panics() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            #[DeriveTryFrom]
+            enum Enum<'a, U> {
+                F1(&'a U),
+                F2(bool),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        _ = fetch_fields_from_enum(&mut ast);
+    }
+
     #[test]
     fn test_generics_and_bounds() {
         let ast: DeriveInput = syn::parse_str(ENUM).expect("Test failed.");
@@ -321,6 +874,64 @@ mod test_parsers {
         assert_eq!(where_clause, "where T : Into < U > , U : 'a");
     }
 
+    /// Test that default values of generic parameters are
+    /// removed from the impl generics but bounds are kept.
+    #[test]
+    fn test_generics_with_defaults() {
+        let ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Value<'a, T: Clone = String, const N: usize = 4>
+            where T: 'a,
+            {
+                Text(&'a T),
+                Bytes([u8; N]),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let (name, lifetimes) = fetch_name_with_generic_params(&ast);
+        assert_eq!(name, "Value<'a,T,{ N }>");
+        let ImplGenerics {
+            impl_generics,
+            impl_generics_ref,
+            where_clause,
+        } = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &lifetimes);
+        assert_eq!(impl_generics, "< 'a , T : Clone , const N : usize >");
+        assert_eq!(
+            impl_generics_ref,
+            "< 'a , 'enum_conv : 'a , T : Clone , const N : usize , >"
+        );
+        assert_eq!(where_clause, "where T : 'a");
+
+        let ast: DeriveInput = syn::parse_str("enum Enum<T> where { F1(T) }").expect("Test failed");
+        let ImplGenerics { where_clause, .. } = fetch_impl_generics(&ast, ENUM_CONV_LIFETIME, &[]);
+        assert_eq!(where_clause, "");
+    }
+
+    /// Defaulted const parameters are named by braced arguments,
+    /// which still resolve to variables when checking collisions.
+    #[test]
+    fn test_name_with_const_default() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum<const N: usize = 3> {
+                F1([u8; N]),
+                F2(bool),
+                F3(Box<Self>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let (name, lifetimes) = fetch_name_with_generic_params(&ast);
+        assert_eq!(name, "Enum<{ N }>");
+        assert!(lifetimes.is_empty());
+        let types = fetch_fields_from_enum(&mut ast)
+            .into_iter()
+            .map(|info| info.ty)
+            .collect::<Vec<String>>();
+        assert_eq!(types, vec!["[u8 ; N]", "bool", "Box < Enum < { N } > >"]);
+    }
+
     #[test]
     fn test_get_name_with_generics() {
         let ast: DeriveInput = syn::parse_str(ENUM).expect("Test failed.");
@@ -329,6 +940,86 @@ mod test_parsers {
         assert_eq!(lifetimes, vec![String::from("'a"), String::from("'b")]);
     }
 
+    /// The attribute is stripped and the pointed to type recorded.
+    #[test]
+    fn test_transparent() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                #[Transparent]
+                F1(Box<Vec<u8>>),
+                #[Transparent]
+                F2(std::sync::Arc<str>),
+                F3(std::rc::Rc<bool>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        let transparent = fetch_fields_from_enum(&mut ast)
+            .into_iter()
+            .map(|info| info.transparent)
+            .collect::<Vec<Option<Transparent>>>();
+        assert_eq!(
+            transparent,
+            vec![
+                Some(Transparent {
+                    wrapper: Wrapper::Box,
+                    inner: "Vec < u8 >".to_string(),
+                    is_unsized: false,
+                }),
+                Some(Transparent {
+                    wrapper: Wrapper::Arc,
+                    inner: "str".to_string(),
+                    is_unsized: true,
+                }),
+                None,
+            ]
+        );
+        let variants = match ast.data {
+            Data::Enum(data) => data.variants,
+            _ => panic!("Test failed"),
+        };
+        assert!(variants.iter().all(|var| var.attrs.is_empty()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The `Transparent` attribute can only be placed on variants holding a `Box`, \
+                    `Rc` or `Arc`. Found `Vec < u8 >` in variant `F1`."
+    )]
+    fn test_transparent_not_a_pointer() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                #[Transparent]
+                F1(Vec<u8>),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        _ = fetch_fields_from_enum(&mut ast);
+    }
+
+    /// The pointed to type must not be held by another variant.
+    #[test]
+    #[should_panic(
+        expected = "Variants `F1` and `F2` of enum `Enum` can hold the same type: with T = u8, \
+                    both are `u8`."
+    )]
+    fn test_transparent_collision() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum<T> {
+                #[Transparent]
+                F1(Box<T>),
+                F2(u8),
+            }
+        "#,
+        )
+        .expect("Test failed");
+        _ = fetch_fields_from_enum(&mut ast);
+    }
+
     #[test]
     #[should_panic(expected = "Can only derive for enums.")]
     fn test_panic_on_struct() {
@@ -367,6 +1058,21 @@ mod test_parsers {
         _ = fetch_fields_from_enum(&mut ast);
     }
 
+    #[test]
+    #[should_panic(expected = "Variants `Ref1` and `Ref2` of enum `Enum` can hold the same type")]
+    fn test_generic_fields_same_type() {
+        let mut ast = syn::parse_str(
+            r#"
+        enum Enum<'a, 'b, U, T> {
+            Ref1(&'a U),
+            Ref2(&'b T),
+        }
+        "#,
+        )
+        .expect("Test failed");
+        _ = fetch_fields_from_enum(&mut ast);
+    }
+
     #[test]
     #[should_panic(
         expected = "Can only derive for enums whose types do not contain multiple fields."
@@ -408,6 +1114,34 @@ mod test_parsers {
         assert!(fields.is_empty())
     }
 
+    /// Test that `#[cfg]` and `#[deprecated]` attributes end
+    /// up on the marker module and the markers.
+    #[test]
+    fn test_create_marker_structs_with_attrs() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            #[cfg(unix)]
+            enum Enum {
+                #[cfg(feature = "tls")]
+                #[deprecated]
+                F1(u64)
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let cfg = fetch_propagated_attrs(&ast.attrs).cfg;
+        let output = create_marker_enums(&ast.ident.to_string(), "", &cfg, &fields, "");
+        assert_eq!(
+            output,
+            "# [cfg (unix)] #[allow(non_snake_case)]\n \
+             #[doc = \"Marker types for the variants of `Enum`.\"]\n \
+             mod enum___conversion___Enum{ \
+             # [cfg (unix)] # [cfg (feature = \"tls\")] #[allow(deprecated)] # [deprecated] \
+             #[doc = \"Marker type for the variant `Enum::F1`.\"] pub enum F1{}}"
+        );
+    }
+
     #[test]
     fn test_create_marker_structs() {
         let mut ast = syn::parse_str(
@@ -419,10 +1153,129 @@ mod test_parsers {
         )
         .expect("Test failed.");
         let fields = fetch_fields_from_enum(&mut ast);
-        let output = create_marker_enums(&ast.ident.to_string(), &fields);
+        let output = create_marker_enums(&ast.ident.to_string(), "", "", &fields, "");
         assert_eq!(
             output,
-            "#[allow(non_snake_case)]\n mod enum___conversion___Enum{ pub(crate) enum F1{}}"
+            "#[allow(non_snake_case)]\n #[doc = \"Marker types for the variants of `Enum`.\"]\n \
+             mod enum___conversion___Enum{ \
+             #[doc = \"Marker type for the variant `Enum::F1`.\"] pub enum F1{}}"
         );
     }
+
+    /// Test that the views keep the generics and where clause
+    /// of the enum and the attributes of its variants.
+    #[test]
+    fn test_create_view_enum() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub enum Enum<T> where T: Clone {
+                #[cfg(unix)]
+                F1(Vec<T>),
+                F2(bool),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let output = create_view_enum(
+            &ast,
+            "EnumMut",
+            "'enum_view",
+            "&'enum_view mut",
+            "",
+            &fields,
+        );
+        assert_eq!(
+            output,
+            "#[doc = \"A view of [`Enum`] borrowing the value of its active variant.\"] \
+             pub enum EnumMut< 'enum_view , T > where T : Clone { \
+             # [cfg (unix)] F1(&'enum_view mut Vec < T >), F2(&'enum_view mut bool), }"
+        );
+    }
+
+    #[test]
+    fn test_pretty_type() {
+        let mut ast: DeriveInput = syn::parse_str(ENUM).expect("Test failed.");
+        let types = fetch_fields_from_enum(&mut ast)
+            .iter()
+            .map(|info| pretty_type(&info.ty))
+            .collect::<Vec<String>>();
+        assert_eq!(
+            types,
+            vec![
+                "[u8; 20]",
+                "fn(&'a usize) -> bool",
+                "typey!()",
+                "<Vec<&'a mut T> as IntoIterator>::Item",
+                "*const u8",
+                "(&'b i64, bool)",
+                "[u8]",
+                "Box<&dyn Into<U>>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snake_case() {
+        let names = [
+            "Int",
+            "HttpRequest",
+            "HTTPRequest",
+            "F1",
+            "Vec2D",
+            "Already_Snake",
+        ]
+        .iter()
+        .map(|name| to_snake_case(name))
+        .collect::<Vec<String>>();
+        assert_eq!(
+            names,
+            vec![
+                "int",
+                "http_request",
+                "http_request",
+                "f1",
+                "vec2_d",
+                "already_snake"
+            ]
+        );
+    }
+
+    /// The markers are created in the order the variants are declared.
+    #[test]
+    fn test_marker_order() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            enum Enum {
+                Zebra(u64),
+                Aardvark(u32),
+                Moose(u16),
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let output = create_marker_enums(&ast.ident.to_string(), "", "", &fields, "");
+        let zebra = output.find("pub enum Zebra").expect("Test failed");
+        let aardvark = output.find("pub enum Aardvark").expect("Test failed");
+        let moose = output.find("pub enum Moose").expect("Test failed");
+        assert!(zebra < aardvark && aardvark < moose);
+    }
+
+    /// The marker module has the visibility of the enum.
+    #[test]
+    fn test_marker_visibility() {
+        let mut ast: DeriveInput = syn::parse_str(
+            r#"
+            pub(crate) enum Enum {
+                F1(u64)
+            }
+        "#,
+        )
+        .expect("Test failed.");
+        let fields = fetch_fields_from_enum(&mut ast);
+        let vis = ast.vis.to_token_stream().to_string();
+        let output = create_marker_enums(&ast.ident.to_string(), &vis, "", &fields, "");
+        assert!(output.contains("pub (crate) mod enum___conversion___Enum{ "));
+    }
 }
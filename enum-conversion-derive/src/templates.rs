@@ -4,15 +4,44 @@ pub(crate) fn templater() -> Tera {
     let mut tera = Tera::new("/dev/null/*").unwrap();
     tera.add_raw_template("get_variant", GET_VARIANT_TEMPLATE)
         .unwrap();
+    tera.add_raw_template("contains_variant", CONTAINS_VARIANT_TEMPLATE)
+        .unwrap();
     tera.add_raw_template("try_from", TRY_FROM_TEMPLATE)
         .unwrap();
     tera.add_raw_template("try_to", TRY_TO_TEMPLATE).unwrap();
     tera.add_raw_template("from", FROM_TEMPLATE).unwrap();
+    tera.add_raw_template("variants", VARIANTS_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("marker_index", MARKER_INDEX_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("kind", KIND_TEMPLATE).unwrap();
+    tera.add_raw_template("marker_kind", MARKER_KIND_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("set", SET_TEMPLATE).unwrap();
+    tera.add_raw_template("visitor", VISITOR_TEMPLATE).unwrap();
+    tera.add_raw_template("views", VIEWS_TEMPLATE).unwrap();
+    tera.add_raw_template("buckets", BUCKETS_TEMPLATE).unwrap();
+    tera.add_raw_template("bucket", BUCKET_TEMPLATE).unwrap();
+    tera.add_raw_template("transition", TRANSITION_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("variant_bound", VARIANT_BOUND_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("pin_checks", PIN_CHECKS_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("try_to_pin", TRY_TO_PIN_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("transparent_from", TRANSPARENT_FROM_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("transparent_ref", TRANSPARENT_REF_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("transparent_box", TRANSPARENT_BOX_TEMPLATE)
+        .unwrap();
+    tera.add_raw_template("accept", ACCEPT_TEMPLATE).unwrap();
     tera
 }
 
 pub(crate) const GET_VARIANT_TEMPLATE: &str = r#"
-impl{{ generics }} enum_conversion_traits::GetVariant<{{ Type }}, {{ Marker }} > for {{ fullname }}
+{{ Attrs }}impl{{ generics }} enum_conversion_traits::GetVariant<{{ Type }}, {{ Marker }} > for {{ fullname }}
 {{ Where }} {
     #[allow(unreachable_patterns)]
     fn get_variant(self) -> std::result::Result<{{ Type }}, enum_conversion_traits::EnumConversionError> {
@@ -40,8 +69,15 @@ impl{{ generics }} enum_conversion_traits::GetVariant<{{ Type }}, {{ Marker }} >
 }
 "#;
 
+pub(crate) const CONTAINS_VARIANT_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics }} enum_conversion_traits::ContainsVariant<{{ Type }}> for {{ fullname }}
+{{ Where }} {
+    type Marker = {{ Marker }};
+}
+"#;
+
 pub(crate) const TRY_TO_TEMPLATE: &str = r#"
-impl{{ generics }} TryTo<{{ Type }}> for {{ fullname }}
+{{ Attrs }}impl{{ generics }} TryTo<{{ Type }}> for {{ fullname }}
 {{ Where }}
 {
     type Error = {{ Error }};
@@ -51,7 +87,7 @@ impl{{ generics }} TryTo<{{ Type }}> for {{ fullname }}
     }
 }
 
-impl{{ generics_ref }} TryTo<&{{ Lifetime }} {{ Type }}> for &{{ Lifetime }} {{ fullname }}
+{{ Attrs }}impl{{ generics_ref }} TryTo<&{{ Lifetime }} {{ Type }}> for &{{ Lifetime }} {{ fullname }}
 {{ Where }}
 {
     type Error = {{ Error }};
@@ -61,7 +97,7 @@ impl{{ generics_ref }} TryTo<&{{ Lifetime }} {{ Type }}> for &{{ Lifetime }} {{
     }
 }
 
-impl{{ generics_ref }} TryTo<&{{ Lifetime }} mut {{ Type }}> for &{{ Lifetime }} mut {{ fullname }}
+{{ Attrs }}impl{{ generics_ref }} TryTo<&{{ Lifetime }} mut {{ Type }}> for &{{ Lifetime }} mut {{ fullname }}
 {{ Where }}
 {
 
@@ -71,10 +107,20 @@ impl{{ generics_ref }} TryTo<&{{ Lifetime }} mut {{ Type }}> for &{{ Lifetime }}
         self.get_variant_mut(){{ Map_Err }}
     }
 }
+
+{{ Attrs }}impl{{ generics }} TryTo<{{ Type }}> for std::boxed::Box<{{ fullname }}>
+{{ Where }}
+{
+    type Error = {{ Error }};
+
+    fn try_to(self) -> std::result::Result<{{ Type }}, Self::Error> {
+        (*self).get_variant(){{ Map_Err }}
+    }
+}
 "#;
 
 pub(crate) const TRY_FROM_TEMPLATE: &str = r#"
-impl{{ generics }} TryFrom<{{ fullname }}> for {{ Type }}
+{{ Attrs }}impl{{ generics }} TryFrom<{{ fullname }}> for {{ Type }}
 {{ Where }}
 {
     type Error = {{ Error }};
@@ -84,7 +130,7 @@ impl{{ generics }} TryFrom<{{ fullname }}> for {{ Type }}
     }
 }
 
-impl{{ generics_ref }} TryFrom<&{{ Lifetime }} {{ fullname }}> for &{{ Lifetime}} {{ Type }}
+{{ Attrs }}impl{{ generics_ref }} TryFrom<&{{ Lifetime }} {{ fullname }}> for &{{ Lifetime}} {{ Type }}
 {{ Where }}
 {
     type Error = {{ Error }};
@@ -95,7 +141,7 @@ impl{{ generics_ref }} TryFrom<&{{ Lifetime }} {{ fullname }}> for &{{ Lifetime}
     }
 }
 
-impl{{ generics_ref }} TryFrom<&{{ Lifetime }} mut {{ fullname }}> for &{{ Lifetime }} mut {{ Type }}
+{{ Attrs }}impl{{ generics_ref }} TryFrom<&{{ Lifetime }} mut {{ fullname }}> for &{{ Lifetime }} mut {{ Type }}
 {{ Where }}
 {
     type Error = {{ Error }};
@@ -107,7 +153,7 @@ impl{{ generics_ref }} TryFrom<&{{ Lifetime }} mut {{ fullname }}> for &{{ Lifet
 "#;
 
 pub(crate) const FROM_TEMPLATE: &str = r#"
-impl{{ generics }} From<{{ Type }}> for {{ fullname }}
+{{ Attrs }}impl{{ generics }} From<{{ Type }}> for {{ fullname }}
 {{ Where }}
 {
     fn from(value: {{ Type }}) -> Self {
@@ -115,3 +161,726 @@ impl{{ generics }} From<{{ Type }}> for {{ fullname }}
     }
 }
 "#;
+
+pub(crate) const VARIANTS_TEMPLATE: &str = r#"
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics }} enum_conversion_traits::Variants for {{ fullname }}
+{{ Where }} {
+    const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+    const VARIANT_NAMES: &'static [&'static str] = &[{{ Names }}];
+    const VARIANT_TYPE_NAMES: &'static [&'static str] = &[{{ TypeNames }}];
+
+    fn variant_index(&self) -> usize {
+        match *self {
+{{ Arms }}        }
+    }
+}
+"#;
+
+pub(crate) const MARKER_INDEX_TEMPLATE: &str = r#"
+{{ Attrs }}impl enum_conversion_traits::MarkerIndex for {{ Marker }} {
+    const INDEX: usize = {{ Index }};
+}
+"#;
+
+pub(crate) const KIND_TEMPLATE: &str = r#"
+{{ Attrs }}#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+{{ vis }} enum {{ Kind }} {
+{{ Variants }}}
+
+{{ Attrs }}impl {{ Kind }} {
+    /// The kinds of all variants, in the order they are declared.
+    {{ vis }} const ALL: &'static [{{ Kind }}] = &[{{ All }}];
+
+    /// The name of the variant.
+    {{ vis }} fn name(self) -> &'static str {
+        match self {
+{{ NameArms }}        }
+    }
+}
+
+{{ Attrs }}impl std::fmt::Display for {{ Kind }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+{{ Attrs }}impl std::str::FromStr for {{ Kind }} {
+    type Err = enum_conversion_traits::ParseKindError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+{{ FromStrArms }}            _ => Err(enum_conversion_traits::ParseKindError::new("{{ Kind }}", s)),
+        }
+    }
+}
+
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics }} {{ fullname }}
+{{ Where }} {
+    /// The kind of the active variant.
+    {{ vis }} fn kind(&self) -> {{ Kind }} {
+        match *self {
+{{ KindArms }}        }
+    }
+
+    /// The kind of the variant holding the given type.
+    {{ vis }} fn kind_of<{{ Param }}>() -> {{ Kind }}
+    where
+        Self: enum_conversion_traits::ContainsVariant<{{ Param }}>,
+        <Self as enum_conversion_traits::ContainsVariant<{{ Param }}>>::Marker: enum_conversion_traits::MarkerKind<{{ Kind }}>,
+    {
+        <<Self as enum_conversion_traits::ContainsVariant<{{ Param }}>>::Marker as enum_conversion_traits::MarkerKind<{{ Kind }}>>::KIND
+    }
+}
+"#;
+
+pub(crate) const MARKER_KIND_TEMPLATE: &str = r#"
+{{ Attrs }}impl enum_conversion_traits::MarkerKind<{{ Kind }}> for {{ Marker }} {
+    const KIND: {{ Kind }} = {{ Kind }}::{{ field }};
+}
+"#;
+
+pub(crate) const SET_TEMPLATE: &str = r#"
+{{ Attrs }}/// A set of variants of [`{{ name }}`], stored as one bit per variant.
+{{ vis }} struct {{ Set }}{{ generics }}
+{{ Where }} {
+    bits: [u64; {{ Words }}],
+    _enum: std::marker::PhantomData<fn() -> {{ fullname }}>,
+}
+
+{{ Attrs }}impl{{ generics }} {{ Set }}{{ args }}
+{{ Where }} {
+    /// The set without any variants.
+    {{ vis }} const fn new() -> Self {
+        Self {
+            bits: [0; {{ Words }}],
+            _enum: std::marker::PhantomData,
+        }
+    }
+
+    /// The set of all variants.
+    {{ vis }} const fn all() -> Self {
+        Self::from_kinds({{ Kind }}::ALL)
+    }
+
+    /// The set of the given kinds of variants.
+    {{ vis }} const fn from_kinds(kinds: &[{{ Kind }}]) -> Self {
+        let mut set = Self::new();
+        let mut i = 0;
+        while i < kinds.len() {
+            set = set.with_kind(kinds[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Adds a kind of variant to the set.
+    {{ vis }} const fn with_kind(self, kind: {{ Kind }}) -> Self {
+        self.with_index(kind as usize)
+    }
+
+    /// Adds the variant holding the given type to the set.
+    {{ vis }} const fn with<{{ Param }}>(self) -> Self
+    where
+{{ Bound }}    {
+        self.with_index({{ Index }})
+    }
+
+    /// Adds the variant holding the given type to the set.
+    /// Returns whether it was not already in the set.
+    {{ vis }} fn insert<{{ Param }}>(&mut self) -> bool
+    where
+{{ Bound }}    {
+        self.insert_index({{ Index }})
+    }
+
+    /// Removes the variant holding the given type from the set.
+    /// Returns whether it was in the set.
+    {{ vis }} fn remove<{{ Param }}>(&mut self) -> bool
+    where
+{{ Bound }}    {
+        self.remove_index({{ Index }})
+    }
+
+    /// Adds a kind of variant to the set. Returns whether
+    /// it was not already in the set.
+    {{ vis }} fn insert_kind(&mut self, kind: {{ Kind }}) -> bool {
+        self.insert_index(kind as usize)
+    }
+
+    /// Removes a kind of variant from the set. Returns
+    /// whether it was in the set.
+    {{ vis }} fn remove_kind(&mut self, kind: {{ Kind }}) -> bool {
+        self.remove_index(kind as usize)
+    }
+
+    /// Checks if the active variant of the value is in the set.
+    {{ vis }} fn contains(&self, value: &{{ fullname }}) -> bool {
+        self.contains_index(enum_conversion_traits::Variants::variant_index(value))
+    }
+
+    /// Checks if a kind of variant is in the set.
+    {{ vis }} const fn contains_kind(&self, kind: {{ Kind }}) -> bool {
+        self.contains_index(kind as usize)
+    }
+
+    /// Checks if the variant holding the given type is in the set.
+    {{ vis }} const fn contains_type<{{ Param }}>(&self) -> bool
+    where
+{{ Bound }}    {
+        self.contains_index({{ Index }})
+    }
+
+    /// The number of variants in the set.
+    {{ vis }} const fn len(&self) -> usize {
+        let mut len = 0;
+        let mut i = 0;
+        while i < {{ Words }} {
+            len += self.bits[i].count_ones() as usize;
+            i += 1;
+        }
+        len
+    }
+
+    /// Checks if the set has no variants.
+    {{ vis }} const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all variants from the set.
+    {{ vis }} fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The variants in either set.
+    {{ vis }} const fn union(self, other: Self) -> Self {
+        let mut i = 0;
+        let mut bits = self.bits;
+        while i < {{ Words }} {
+            bits[i] |= other.bits[i];
+            i += 1;
+        }
+        Self::from_bits(bits)
+    }
+
+    /// The variants in both sets.
+    {{ vis }} const fn intersection(self, other: Self) -> Self {
+        let mut i = 0;
+        let mut bits = self.bits;
+        while i < {{ Words }} {
+            bits[i] &= other.bits[i];
+            i += 1;
+        }
+        Self::from_bits(bits)
+    }
+
+    /// The variants in this set but not in the other.
+    {{ vis }} const fn difference(self, other: Self) -> Self {
+        let mut i = 0;
+        let mut bits = self.bits;
+        while i < {{ Words }} {
+            bits[i] &= !other.bits[i];
+            i += 1;
+        }
+        Self::from_bits(bits)
+    }
+
+    /// The variants in exactly one of the sets.
+    {{ vis }} const fn symmetric_difference(self, other: Self) -> Self {
+        let mut i = 0;
+        let mut bits = self.bits;
+        while i < {{ Words }} {
+            bits[i] ^= other.bits[i];
+            i += 1;
+        }
+        Self::from_bits(bits)
+    }
+
+    /// The variants not in the set.
+    {{ vis }} const fn complement(self) -> Self {
+        Self::all().difference(self)
+    }
+
+    /// Checks if every variant in this set is in the other.
+    {{ vis }} const fn is_subset(&self, other: &Self) -> bool {
+        self.difference(*other).is_empty()
+    }
+
+    /// Checks if every variant in the other set is in this one.
+    {{ vis }} const fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Checks if the sets have no variants in common.
+    {{ vis }} const fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(*other).is_empty()
+    }
+
+    /// The kinds of the variants in the set, in the order
+    /// the variants are declared.
+    {{ vis }} fn iter(&self) -> impl Iterator<Item = {{ Kind }}> {
+        // Only the bits are captured, as the returned type
+        // cannot name the lifetimes of the enum.
+        let bits = self.bits;
+        {{ Kind }}::ALL
+            .iter()
+            .copied()
+            .filter(move |kind| {
+                let index = *kind as usize;
+                bits[index / 64] & (1 << (index % 64)) != 0
+            })
+    }
+
+    const fn from_bits(bits: [u64; {{ Words }}]) -> Self {
+        Self {
+            bits,
+            _enum: std::marker::PhantomData,
+        }
+    }
+
+    const fn contains_index(&self, index: usize) -> bool {
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    const fn with_index(mut self, index: usize) -> Self {
+        self.bits[index / 64] |= 1 << (index % 64);
+        self
+    }
+
+    fn insert_index(&mut self, index: usize) -> bool {
+        let absent = !self.contains_index(index);
+        *self = self.with_index(index);
+        absent
+    }
+
+    fn remove_index(&mut self, index: usize) -> bool {
+        let present = self.contains_index(index);
+        self.bits[index / 64] &= !(1 << (index % 64));
+        present
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::clone::Clone for {{ Set }}{{ args }}
+{{ Where }} {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::marker::Copy for {{ Set }}{{ args }}
+{{ Where }} {}
+
+{{ Attrs }}impl{{ generics }} std::cmp::PartialEq for {{ Set }}{{ args }}
+{{ Where }} {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::cmp::Eq for {{ Set }}{{ args }}
+{{ Where }} {}
+
+{{ Attrs }}impl{{ generics }} std::hash::Hash for {{ Set }}{{ args }}
+{{ Where }} {
+    fn hash<{{ Param }}: std::hash::Hasher>(&self, state: &mut {{ Param }}) {
+        self.bits.hash(state);
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::default::Default for {{ Set }}{{ args }}
+{{ Where }} {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::fmt::Debug for {{ Set }}{{ args }}
+{{ Where }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::iter::FromIterator<{{ Kind }}> for {{ Set }}{{ args }}
+{{ Where }} {
+    fn from_iter<{{ Param }}: std::iter::IntoIterator<Item = {{ Kind }}>>(iter: {{ Param }}) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::iter::Extend<{{ Kind }}> for {{ Set }}{{ args }}
+{{ Where }} {
+    fn extend<{{ Param }}: std::iter::IntoIterator<Item = {{ Kind }}>>(&mut self, iter: {{ Param }}) {
+        for kind in iter {
+            self.insert_kind(kind);
+        }
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::ops::BitOr for {{ Set }}{{ args }}
+{{ Where }} {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::ops::BitAnd for {{ Set }}{{ args }}
+{{ Where }} {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::ops::BitXor for {{ Set }}{{ args }}
+{{ Where }} {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        self.symmetric_difference(other)
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::ops::Sub for {{ Set }}{{ args }}
+{{ Where }} {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::ops::Not for {{ Set }}{{ args }}
+{{ Where }} {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+"#;
+
+pub(crate) const VISITOR_TEMPLATE: &str = r#"
+{{ Attrs }}/// Visits the variants of [`{{ name }}`] {{ Mode }}, with a method for each variant.
+#[allow(clippy::ptr_arg)]
+{{ vis }} trait {{ Trait }}{{ generics }}
+{{ Where }} {
+    /// The type returned by the methods of the visitor.
+    type Output;
+{{ Methods }}}
+"#;
+
+pub(crate) const ACCEPT_TEMPLATE: &str = r#"
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics }} {{ fullname }}
+{{ Where }} {
+    /// Calls the method of the visitor for the active variant.
+    {{ vis }} fn accept<{{ Param }}: {{ Visitor }}{{ args }}>(self, visitor: &mut {{ Param }}) -> {{ Param }}::Output {
+        match self {
+{{ Arms }}        }
+    }
+
+    /// Calls the method of the visitor for the active variant
+    /// with a reference to its value.
+    {{ vis }} fn accept_ref<{{ Lifetime }}, {{ Param }}: {{ RefVisitor }}{{ args_ref }}>(&{{ Lifetime }} self, visitor: &mut {{ Param }}) -> {{ Param }}::Output {
+        match self {
+{{ Arms }}        }
+    }
+
+    /// Calls the method of the visitor for the active variant
+    /// with a mutable reference to its value.
+    {{ vis }} fn accept_mut<{{ Lifetime }}, {{ Param }}: {{ MutVisitor }}{{ args_ref }}>(&{{ Lifetime }} mut self, visitor: &mut {{ Param }}) -> {{ Param }}::Output {
+        match self {
+{{ Arms }}        }
+    }
+}
+"#;
+
+pub(crate) const TRANSPARENT_FROM_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics }} From<{{ Inner }}> for {{ fullname }}
+{{ Where }}
+{
+    fn from(value: {{ Inner }}) -> Self {
+        Self::{{ field }}(value.into())
+    }
+}
+"#;
+
+pub(crate) const TRANSPARENT_REF_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics_ref }} TryTo<&{{ Lifetime }} {{ Inner }}> for &{{ Lifetime }} {{ fullname }}
+{{ Where }}
+{
+    type Error = {{ Error }};
+
+    fn try_to(self) -> std::result::Result<&{{ Lifetime }} {{ Inner }}, Self::Error> {
+        self.get_variant_ref().map(|inner: &{{ Lifetime }} {{ Type }}| &**inner)
+            .map_err(|_| enum_conversion_traits::EnumConversionError::new("{{ fullname }}", "{{ Inner }}")){{ Map_Err }}
+    }
+}
+"#;
+
+pub(crate) const TRANSPARENT_BOX_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics }} TryTo<{{ Inner }}> for {{ fullname }}
+{{ Where }}
+{
+    type Error = {{ Error }};
+
+    fn try_to(self) -> std::result::Result<{{ Inner }}, Self::Error> {
+        self.get_variant().map(|inner: {{ Type }}| *inner)
+            .map_err(|_| enum_conversion_traits::EnumConversionError::new("{{ fullname }}", "{{ Inner }}")){{ Map_Err }}
+    }
+}
+
+{{ Attrs }}impl{{ generics_ref }} TryTo<&{{ Lifetime }} mut {{ Inner }}> for &{{ Lifetime }} mut {{ fullname }}
+{{ Where }}
+{
+    type Error = {{ Error }};
+
+    fn try_to(self) -> std::result::Result<&{{ Lifetime }} mut {{ Inner }}, Self::Error> {
+        self.get_variant_mut().map(|inner: &{{ Lifetime }} mut {{ Type }}| &mut **inner)
+            .map_err(|_| enum_conversion_traits::EnumConversionError::new("{{ fullname }}", "{{ Inner }}")){{ Map_Err }}
+    }
+}
+"#;
+
+pub(crate) const PIN_CHECKS_TEMPLATE: &str = r#"
+{{ Attrs }}const _: () = {
+    #[doc(hidden)]
+    #[allow(dead_code)]
+    pub struct __EnumConvUnpin{{ generics_lt }}
+    {{ Where }} {
+        _enum: std::marker::PhantomData<(&{{ Lifetime }} (), fn() -> {{ fullname }})>,
+{{ Fields }}    }
+
+    impl{{ generics_lt }} std::marker::Unpin for {{ fullname }}
+    {{ Unpin_Where }} {}
+
+    trait {{ name }}MustNotImplDrop {}
+
+    #[allow(drop_bounds)]
+    impl<T: std::ops::Drop> {{ name }}MustNotImplDrop for T {}
+
+    impl{{ generics }} {{ name }}MustNotImplDrop for {{ fullname }}
+    {{ Where }} {}
+};
+"#;
+
+pub(crate) const TRY_TO_PIN_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics_ref }} TryTo<std::pin::Pin<&{{ Lifetime }} {{ Type }}>> for std::pin::Pin<&{{ Lifetime }} {{ fullname }}>
+{{ Where }}
+{
+    type Error = {{ Error }};
+
+    fn try_to(self) -> std::result::Result<std::pin::Pin<&{{ Lifetime }} {{ Type }}>, Self::Error> {
+        let inner = self.get_ref().get_variant_ref(){{ Map_Err }}?;
+        // SAFETY: The value is pinned as the enum is pinned, see `impl_pin`.
+        Ok(unsafe { std::pin::Pin::new_unchecked(inner) })
+    }
+}
+
+{{ Attrs }}impl{{ generics_ref }} TryTo<std::pin::Pin<&{{ Lifetime }} mut {{ Type }}>> for std::pin::Pin<&{{ Lifetime }} mut {{ fullname }}>
+{{ Where }}
+{
+    type Error = {{ Error }};
+
+    fn try_to(self) -> std::result::Result<std::pin::Pin<&{{ Lifetime }} mut {{ Type }}>, Self::Error> {
+        // SAFETY: The value is not moved out of the enum, and is
+        // pinned as the enum is pinned, see `impl_pin`.
+        let inner = unsafe { self.get_unchecked_mut() }.get_variant_mut(){{ Map_Err }}?;
+        Ok(unsafe { std::pin::Pin::new_unchecked(inner) })
+    }
+}
+"#;
+
+pub(crate) const VIEWS_TEMPLATE: &str = r#"
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics }} {{ fullname }}
+{{ Where }} {
+    /// Borrows the value of the active variant.
+    {{ vis }} fn as_ref_enum(&self) -> {{ Ref }}{{ args_anon }} {
+        match self {
+{{ RefArms }}        }
+    }
+
+    /// Mutably borrows the value of the active variant.
+    {{ vis }} fn as_mut_enum(&mut self) -> {{ Mut }}{{ args_anon }} {
+        match self {
+{{ MutArms }}        }
+    }
+}
+
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics_view }} {{ Ref }}{{ args_view }}
+{{ Where }} {
+    /// Clones the borrowed value into a new enum.
+    {{ vis }} fn to_owned_enum(&self) -> {{ fullname }}
+    where
+{{ CloneBounds }}    {
+        match self {
+{{ ToOwnedRefArms }}        }
+    }
+}
+
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics_view }} {{ Mut }}{{ args_view }}
+{{ Where }} {
+    /// Clones the borrowed value into a new enum.
+    {{ vis }} fn to_owned_enum(&self) -> {{ fullname }}
+    where
+{{ CloneBounds }}    {
+        match self {
+{{ ToOwnedMutArms }}        }
+    }
+}
+
+{{ Attrs }}impl{{ generics_view }} Clone for {{ Ref }}{{ args_view }}
+{{ Where }} {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+{{ Attrs }}impl{{ generics_view }} Copy for {{ Ref }}{{ args_view }}
+{{ Where }} {}
+"#;
+
+pub(crate) const VARIANT_BOUND_TEMPLATE: &str = r#"
+{{ Cfg }}#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub type {{ Alias }}{{ generics }} = enum_conversion_traits::Deferred<{{ Lifetime }}, {{ Type }}, {{ Phantom }}>;
+
+{{ NotCfg }}#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub type {{ Alias }}{{ generics }} = {{ Phantom }};
+"#;
+
+pub(crate) const BUCKETS_TEMPLATE: &str = r#"
+{{ Attrs }}/// A collection of [`{{ name }}`] storing the values of each variant in a separate `Vec`.
+#[allow(non_snake_case)]
+{{ vis }} struct {{ Buckets }}{{ generics }}
+{{ Where }} {
+    order: std::vec::Vec<usize>,
+{{ Fields }}}
+
+{{ Attrs }}#[allow(deprecated)]
+impl{{ generics }} {{ Buckets }}{{ args }}
+{{ Where }} {
+    /// Creates empty buckets.
+    {{ vis }} fn new() -> Self {
+        Self {
+            order: std::vec::Vec::new(),
+{{ NewFields }}        }
+    }
+
+    /// The number of values in all buckets.
+    {{ vis }} fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Checks if all buckets are empty.
+    {{ vis }} fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Adds the value of the active variant to its bucket.
+    {{ vis }} fn push(&mut self, value: {{ fullname }}) {
+        match value {
+{{ PushArms }}        }
+    }
+
+    /// The values of the variant holding the given type,
+    /// in the order they were added.
+    {{ vis }} fn bucket<{{ Param }}>(&self) -> &[{{ Param }}]
+    where
+        Self: enum_conversion_traits::Bucket<{{ Param }}>,
+    {
+        enum_conversion_traits::Bucket::get_bucket(self)
+    }
+
+    /// Mutably borrows the values of the variant holding
+    /// the given type, in the order they were added.
+    {{ vis }} fn bucket_mut<{{ Param }}>(&mut self) -> &mut [{{ Param }}]
+    where
+        Self: enum_conversion_traits::Bucket<{{ Param }}>,
+    {
+        enum_conversion_traits::Bucket::get_bucket_mut(self)
+    }
+
+    /// Turns the values back into enums, in the order they were added.
+    {{ vis }} fn into_iter_ordered(self) -> impl Iterator<Item = {{ fullname }}> {
+        let Self {
+            order,
+{{ Bindings }}        } = self;
+{{ IntoIters }}        order.into_iter().map(move |index| match index {
+{{ OrderArms }}            _ => unreachable!("The order only holds indices of variants."),
+        })
+    }
+}
+
+{{ Attrs }}impl{{ generics_bound }} std::fmt::Debug for {{ Buckets }}{{ args }}
+{{ DebugWhere }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut debug = f.debug_struct("{{ Buckets }}");
+        debug.field("order", &self.order);
+{{ DebugFields }}        debug.finish()
+    }
+}
+
+{{ Attrs }}impl{{ generics_bound }} std::clone::Clone for {{ Buckets }}{{ args }}
+{{ CloneWhere }} {
+    fn clone(&self) -> Self {
+        Self {
+            order: self.order.clone(),
+{{ CloneFields }}        }
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::default::Default for {{ Buckets }}{{ args }}
+{{ Where }} {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::iter::Extend<{{ fullname }}> for {{ Buckets }}{{ args }}
+{{ Where }} {
+    fn extend<{{ Param }}: std::iter::IntoIterator<Item = {{ fullname }}>>(&mut self, iter: {{ Param }}) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+{{ Attrs }}impl{{ generics }} std::iter::FromIterator<{{ fullname }}> for {{ Buckets }}{{ args }}
+{{ Where }} {
+    fn from_iter<{{ Param }}: std::iter::IntoIterator<Item = {{ fullname }}>>(iter: {{ Param }}) -> Self {
+        let mut buckets = Self::new();
+        buckets.extend(iter);
+        buckets
+    }
+}
+"#;
+
+pub(crate) const BUCKET_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics }} enum_conversion_traits::Bucket<{{ Type }}> for {{ Buckets }}{{ args }}
+{{ Where }} {
+    fn get_bucket(&self) -> &[{{ Type }}] {
+        &self.{{ bucket }}
+    }
+
+    fn get_bucket_mut(&mut self) -> &mut [{{ Type }}] {
+        &mut self.{{ bucket }}
+    }
+}
+"#;
+
+pub(crate) const TRANSITION_TEMPLATE: &str = r#"
+{{ Attrs }}impl{{ generics }} enum_conversion_traits::AllowedTransition<{{ Old }}, {{ New }}> for {{ fullname }}
+{{ Where }} {}
+"#;
@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::{Expr, GenericArgument, Generics, PathArguments, Type, WherePredicate};
+
+use crate::parse_attributes::cfgs_exclusive;
+
+/// The shape of a node in a [`Term`]. Two nodes can only
+/// be unified if their shapes are equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Shape {
+    /// A path such as `Vec` or `std::rc::Rc`. Its generic
+    /// arguments are the children of the node.
+    Path(String),
+    Ref,
+    RefMut,
+    PtrConst,
+    PtrMut,
+    Slice,
+    Array,
+    Tuple,
+    /// Types we do not look inside of, such as trait objects,
+    /// function pointers or associated types. These are only
+    /// equal if their tokens are equal. The variables mentioned
+    /// in the tokens are the children of the node, so that the
+    /// occurs check sees them.
+    Opaque(String),
+}
+
+/// A simplified representation of a type for the purpose
+/// of unification. Lifetimes are dropped, as they play no
+/// role when the compiler checks for overlapping impls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    /// A generic type or const parameter of the enum.
+    Var(String),
+    Node(Shape, Vec<Term>),
+}
+
+impl Term {
+    /// Converts a type into a term, treating the
+    /// identifiers in `vars` as variables.
+    fn from_type(ty: &Type, vars: &[String]) -> Term {
+        match ty {
+            Type::Paren(paren) => Term::from_type(&paren.elem, vars),
+            Type::Group(group) => Term::from_type(&group.elem, vars),
+            Type::Reference(reference) => {
+                let shape = if reference.mutability.is_some() {
+                    Shape::RefMut
+                } else {
+                    Shape::Ref
+                };
+                Term::Node(shape, vec![Term::from_type(&reference.elem, vars)])
+            }
+            Type::Ptr(ptr) => {
+                let shape = if ptr.mutability.is_some() {
+                    Shape::PtrMut
+                } else {
+                    Shape::PtrConst
+                };
+                Term::Node(shape, vec![Term::from_type(&ptr.elem, vars)])
+            }
+            Type::Slice(slice) => {
+                Term::Node(Shape::Slice, vec![Term::from_type(&slice.elem, vars)])
+            }
+            Type::Array(array) => Term::Node(
+                Shape::Array,
+                vec![
+                    Term::from_type(&array.elem, vars),
+                    Term::from_expr(&array.len, vars),
+                ],
+            ),
+            Type::Tuple(tuple) => Term::Node(
+                Shape::Tuple,
+                tuple
+                    .elems
+                    .iter()
+                    .map(|elem| Term::from_type(elem, vars))
+                    .collect(),
+            ),
+            Type::Path(path) if path.qself.is_none() => {
+                if let Some(ident) = path.path.get_ident() {
+                    let ident = ident.to_string();
+                    if vars.contains(&ident) {
+                        return Term::Var(ident);
+                    }
+                }
+                let mut segments = vec![];
+                let mut children = vec![];
+                for segment in path.path.segments.iter() {
+                    segments.push(segment.ident.to_string());
+                    match &segment.arguments {
+                        PathArguments::AngleBracketed(args) => {
+                            for arg in args.args.iter() {
+                                match arg {
+                                    GenericArgument::Lifetime(_) => {}
+                                    GenericArgument::Type(ty) => {
+                                        children.push(Term::from_type(ty, vars))
+                                    }
+                                    GenericArgument::Const(expr) => {
+                                        children.push(Term::from_expr(expr, vars))
+                                    }
+                                    _ => children.push(Term::opaque(arg, vars)),
+                                }
+                            }
+                        }
+                        PathArguments::Parenthesized(args) => {
+                            children.push(Term::opaque(args, vars))
+                        }
+                        PathArguments::None => {}
+                    }
+                }
+                let mut name = segments.join("::");
+                if path.path.leading_colon.is_some() {
+                    name.insert_str(0, "::");
+                }
+                Term::Node(Shape::Path(name), children)
+            }
+            _ => Term::opaque(ty, vars),
+        }
+    }
+
+    /// Const arguments are variables if they are the name
+    /// of a const generic parameter.
+    fn from_expr(expr: &Expr, vars: &[String]) -> Term {
+        match expr {
+            Expr::Path(path) if path.qself.is_none() => match path.path.get_ident() {
+                Some(ident) if vars.contains(&ident.to_string()) => Term::Var(ident.to_string()),
+                _ => Term::opaque(expr, vars),
+            },
+            Expr::Block(block) if block.block.stmts.len() == 1 => match &block.block.stmts[0] {
+                syn::Stmt::Expr(inner) => Term::from_expr(inner, vars),
+                _ => Term::opaque(expr, vars),
+            },
+            _ => Term::opaque(expr, vars),
+        }
+    }
+
+    fn opaque<T: ToTokens>(tokens: &T, vars: &[String]) -> Term {
+        let tokens = tokens.to_token_stream();
+        let mut mentioned = vec![];
+        mentioned_vars(tokens.clone(), vars, &mut mentioned);
+        Term::Node(
+            Shape::Opaque(tokens.to_string()),
+            mentioned.into_iter().map(Term::Var).collect(),
+        )
+    }
+
+    /// Applies the substitution to this term.
+    fn resolve(&self, subst: &HashMap<String, Term>) -> Term {
+        match self {
+            Term::Var(var) => match subst.get(var) {
+                Some(term) => term.resolve(subst),
+                None => self.clone(),
+            },
+            Term::Node(shape, children) => Term::Node(
+                shape.clone(),
+                children.iter().map(|child| child.resolve(subst)).collect(),
+            ),
+        }
+    }
+
+    fn contains(&self, var: &str) -> bool {
+        match self {
+            Term::Var(other) => other == var,
+            Term::Node(_, children) => children.iter().any(|child| child.contains(var)),
+        }
+    }
+}
+
+/// Collects the identifiers in `vars` that appear in the tokens,
+/// each only once and in the order they first appear.
+fn mentioned_vars(tokens: TokenStream2, vars: &[String], mentioned: &mut Vec<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                let ident = ident.to_string();
+                if vars.contains(&ident) && !mentioned.contains(&ident) {
+                    mentioned.push(ident);
+                }
+            }
+            TokenTree::Group(group) => mentioned_vars(group.stream(), vars, mentioned),
+            _ => {}
+        }
+    }
+}
+
+/// Collects the type parameters in `vars` that have a trait bound,
+/// either where they are declared or in the where clause.
+pub(crate) fn bounded_params(generics: &Generics, vars: &[String]) -> Vec<String> {
+    let mut bounded = generics
+        .type_params()
+        .filter(|param| !param.bounds.is_empty())
+        .map(|param| param.ident.to_string())
+        .collect::<Vec<String>>();
+    for predicate in generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| &clause.predicates)
+    {
+        if let WherePredicate::Type(predicate) = predicate {
+            mentioned_vars(predicate.bounded_ty.to_token_stream(), vars, &mut bounded);
+        }
+    }
+    bounded
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (shape, children) = match self {
+            Term::Var(var) => return write!(f, "{}", var),
+            Term::Node(shape, children) => (shape, children),
+        };
+        let joined = children
+            .iter()
+            .map(|child| child.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        match shape {
+            Shape::Path(name) if children.is_empty() => write!(f, "{}", name),
+            Shape::Path(name) => write!(f, "{}<{}>", name, joined),
+            Shape::Ref => write!(f, "&{}", joined),
+            Shape::RefMut => write!(f, "&mut {}", joined),
+            Shape::PtrConst => write!(f, "*const {}", joined),
+            Shape::PtrMut => write!(f, "*mut {}", joined),
+            Shape::Slice => write!(f, "[{}]", joined),
+            Shape::Array => write!(f, "[{}; {}]", children[0], children[1]),
+            Shape::Tuple if children.len() == 1 => write!(f, "({},)", joined),
+            Shape::Tuple => write!(f, "({})", joined),
+            Shape::Opaque(tokens) => write!(f, "{}", tokens),
+        }
+    }
+}
+
+/// Tries to extend the substitution so that both
+/// terms become equal. Returns `false` if this is
+/// not possible.
+fn unify(left: &Term, right: &Term, subst: &mut HashMap<String, Term>) -> bool {
+    let left = left.resolve(subst);
+    let right = right.resolve(subst);
+    match (&left, &right) {
+        (Term::Var(l), Term::Var(r)) if l == r => true,
+        (Term::Var(var), term) | (term, Term::Var(var)) => {
+            if term.contains(var) {
+                return false;
+            }
+            subst.insert(var.clone(), term.clone());
+            true
+        }
+        (Term::Node(l_shape, l_children), Term::Node(r_shape, r_children)) => {
+            l_shape == r_shape
+                && l_children.len() == r_children.len()
+                && l_children
+                    .iter()
+                    .zip(r_children.iter())
+                    .all(|(l, r)| unify(l, r, subst))
+        }
+    }
+}
+
+/// Checks that no two variants of the enum can hold the same type
+/// for some choice of the enum's generic parameters. Otherwise the
+/// generated trait implementations would conflict.
+///
+/// Variants whose `#[cfg]` attributes, given with their types, are
+/// exclusive never exist together, so are not compared.
+///
+/// This is a purely syntactic check. Type aliases and associated
+/// types are not taken into account. A trait bound on a parameter in
+/// `bounded` may rule out the types it would need to equal, as in
+/// ```
+/// struct Local;
+/// enum Enum<T: Copy> {
+///     F1(T),
+///     F2(Local),
+/// }
+/// ```
+/// so collisions instantiating such a parameter with anything but
+/// another parameter are left for the compiler to decide.
+///
+/// # Example
+/// ```
+/// struct Local<T>(T);
+/// enum Enum<U, T> {
+///     F1(Local<U>),
+///     F2(Local<T>),
+/// }
+/// ```
+/// The two variants hold the same type if `U = T`, so this panics.
+pub(crate) fn check_collisions(
+    name: &str,
+    variants: &[(String, Type, String)],
+    vars: &[String],
+    bounded: &[String],
+) {
+    let terms = variants
+        .iter()
+        .map(|(field, ty, cfg)| (field, Term::from_type(ty, vars), cfg))
+        .collect::<Vec<(&String, Term, &String)>>();
+    for (ix, (field_1, term_1, cfg_1)) in terms.iter().enumerate() {
+        for (field_2, term_2, cfg_2) in terms.iter().skip(ix + 1) {
+            if cfgs_exclusive(cfg_1, cfg_2) {
+                continue;
+            }
+            let mut subst = HashMap::new();
+            if !unify(term_1, term_2, &mut subst) {
+                continue;
+            }
+            // A bound may rule out the type the parameter takes.
+            let resolved_bounded = bounded.iter().any(|var| {
+                subst
+                    .get(var)
+                    .is_some_and(|term| matches!(term.resolve(&subst), Term::Node(..)))
+            });
+            if resolved_bounded {
+                continue;
+            }
+            let mut instantiation = vars
+                .iter()
+                .filter_map(|var| {
+                    subst
+                        .get(var)
+                        .map(|term| format!("{} = {}", var, term.resolve(&subst)))
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            if instantiation.is_empty() {
+                instantiation.push_str("any parameters");
+            }
+            panic!(
+                "Variants `{}` and `{}` of enum `{}` can hold the same type: with {}, \
+                 both are `{}`. Each type may only appear in a single variant.",
+                field_1,
+                field_2,
+                name,
+                instantiation,
+                term_1.resolve(&subst),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_unify {
+    use super::*;
+
+    fn variants(types: &[&str]) -> Vec<(String, Type, String)> {
+        types
+            .iter()
+            .enumerate()
+            .map(|(ix, ty)| {
+                (
+                    format!("F{}", ix + 1),
+                    syn::parse_str(ty).expect("Test failed"),
+                    String::new(),
+                )
+            })
+            .collect()
+    }
+
+    fn vars(vars: &[&str]) -> Vec<String> {
+        vars.iter().map(|var| var.to_string()).collect()
+    }
+
+    /// Types that cannot be made equal pass the check.
+    #[test]
+    fn test_no_collisions() {
+        let variants = variants(&[
+            "Local<U>",
+            "Local<(U, T)>",
+            "Vec<U>",
+            "&'a mut T",
+            "[T; 3]",
+            "fn(U) -> bool",
+            "<U as IntoIterator>::Item",
+            "*const T",
+        ]);
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &[]);
+    }
+
+    /// A type parameter can never equal a type containing it.
+    #[test]
+    fn test_occurs_check() {
+        let variants = variants(&["Box<U>", "Box<Vec<U>>"]);
+        check_collisions("Enum", &variants, &vars(&["U"]), &[]);
+    }
+
+    /// The occurs check also applies to the parameters mentioned
+    /// inside of types we do not look into.
+    #[test]
+    fn test_occurs_check_opaque() {
+        let functions = variants(&["U", "fn(U) -> bool", "Box<dyn Fn(U)>"]);
+        check_collisions("Enum", &functions, &vars(&["U"]), &[]);
+        let arrays = variants(&["[u8; N]", "[u8; { N + 1 }]"]);
+        check_collisions("Enum", &arrays, &vars(&["N"]), &[]);
+    }
+
+    /// The parameters inside opaque types are still substituted.
+    #[test]
+    fn test_occurs_check_opaque_resolved() {
+        let variants = variants(&["(U, T)", "(Vec<T>, fn(U))"]);
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &[]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Variants `F1` and `F2` of enum `Enum` can hold the same type: \
+                               with U = T, both are `Local<T>`."
+    )]
+    fn test_type_params_collide() {
+        let variants = variants(&["Local<U>", "Local<T>"]);
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &[]);
+    }
+
+    /// Lifetimes do not distinguish types.
+    #[test]
+    #[should_panic(
+        expected = "Variants `F1` and `F3` of enum `Enum` can hold the same type: \
+                               with U = bool, both are `&bool`."
+    )]
+    fn test_references_collide() {
+        let variants = variants(&["&'a U", "&'b mut T", "&'b bool"]);
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &[]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Variants `F1` and `F3` of enum `Enum` can hold the same type: \
+                               with N = 4, both are `[u8; 4]`."
+    )]
+    fn test_const_params_collide() {
+        let variants = variants(&["[u8; N]", "[i8; 4]", "[u8; 4]"]);
+        check_collisions("Enum", &variants, &vars(&["N"]), &[]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Variants `F1` and `F2` of enum `Enum` can hold the same type: \
+                               with U = Option<T>, both are `(Option<T>, Option<T>)`."
+    )]
+    fn test_nested_collide() {
+        let variants = variants(&["(U, Option<T>)", "(Option<T>, U)"]);
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &[]);
+    }
+
+    /// Variants with exclusive `#[cfg]` attributes may collide.
+    #[test]
+    fn test_exclusive_cfgs() {
+        let mut variants = variants(&["Local<U>", "Local<T>"]);
+        variants[0].2 = "# [cfg (unix)] ".to_string();
+        variants[1].2 = "# [cfg (not (unix))] ".to_string();
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &[]);
+    }
+
+    /// Bounds on a parameter may rule out the type it is unified
+    /// with, so those collisions are left to the compiler.
+    #[test]
+    fn test_bounded_params() {
+        let ast: syn::DeriveInput =
+            syn::parse_str("enum Enum<U: Copy, T, V> where Vec<T>: Clone {}").expect("Test failed");
+        let bounded = bounded_params(&ast.generics, &vars(&["U", "T", "V"]));
+        assert_eq!(bounded, vec!["U", "T"]);
+        let variants = variants(&["U", "Local", "Option<T>", "Option<Vec<V>>"]);
+        check_collisions("Enum", &variants, &vars(&["U", "T", "V"]), &bounded);
+    }
+
+    /// Parameters unified with each other still collide,
+    /// whatever their bounds.
+    #[test]
+    #[should_panic(expected = "with U = T, both are `T`.")]
+    fn test_bounded_params_collide() {
+        let variants = variants(&["U", "T"]);
+        check_collisions("Enum", &variants, &vars(&["U", "T"]), &vars(&["U", "T"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "with any parameters, both are `Box<&i64>`.")]
+    fn test_identical_types_collide() {
+        let variants = variants(&["Box<&'a i64>", "Box<&'static i64>"]);
+        check_collisions("Enum", &variants, &vars(&[]), &[]);
+    }
+}
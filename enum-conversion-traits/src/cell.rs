@@ -0,0 +1,29 @@
+//! Projections of borrows of a [`std::cell::RefCell`] holding
+//! an enum onto the value of one of its variants.
+
+use std::cell::{Ref, RefMut};
+
+use crate::ContainsVariant;
+
+/// Turns a borrow of an enum into a borrow of its value of
+/// type `T`, or gives back the borrow if a different variant
+/// is active.
+///
+/// As with [`Ref::filter_map`], this is not a method, so
+/// that it cannot shadow the methods of the enum.
+pub fn ref_variant<'b, E, T>(value: Ref<'b, E>) -> Result<Ref<'b, T>, Ref<'b, E>>
+where
+    E: ContainsVariant<T>,
+{
+    Ref::filter_map(value, |value| value.get_variant_ref().ok())
+}
+
+/// Turns a mutable borrow of an enum into a mutable borrow
+/// of its value of type `T`, or gives back the borrow if a
+/// different variant is active.
+pub fn ref_mut_variant<'b, E, T>(value: RefMut<'b, E>) -> Result<RefMut<'b, T>, RefMut<'b, E>>
+where
+    E: ContainsVariant<T>,
+{
+    RefMut::filter_map(value, |value| value.get_variant_mut().ok())
+}
@@ -0,0 +1,152 @@
+//! Splitting a [`std::sync::mpsc`] channel of enums into
+//! channels for the types held by its variants.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::{error::Error, fmt};
+
+use crate::{empty_slots, slot_of, ContainsVariant, MarkerIndex, Variants};
+
+/// Sends the value of a variant on the channel of its type,
+/// giving back the enum if the receiver was dropped.
+type Route<E> = Box<dyn FnMut(E) -> Result<(), E> + Send>;
+
+/// What a [`Demux`] does with values of variants that
+/// no receiver was handed out for.
+pub enum Unclaimed<E> {
+    /// The values are dropped.
+    Drop,
+    /// The values are passed to a handler.
+    Handle(Box<dyn FnMut(E) + Send>),
+    /// Routing stops with a [`DemuxError`] holding the value.
+    Error,
+}
+
+impl<E> fmt::Debug for Unclaimed<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unclaimed::Drop => f.write_str("Drop"),
+            Unclaimed::Handle(_) => f.write_str("Handle(..)"),
+            Unclaimed::Error => f.write_str("Error"),
+        }
+    }
+}
+
+/// The error returned by a [`Demux`] with the [`Unclaimed::Error`]
+/// policy when it receives a value no receiver was handed out for.
+pub struct DemuxError<E> {
+    pub variant: &'static str,
+    pub value: E,
+}
+
+impl<E> fmt::Debug for DemuxError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DemuxError")
+            .field("variant", &self.variant)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E> Error for DemuxError<E> {}
+
+impl<E> fmt::Display for DemuxError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DemuxError :: No receiver for variant <{}> of <{}>",
+            self.variant,
+            std::any::type_name::<E>(),
+        )
+    }
+}
+
+/// Routes the values received on a channel of enums to a channel
+/// for each type held by its variants, as handed out by
+/// [`Demux::subscribe`].
+///
+/// Values of a variant no receiver was handed out for, or whose
+/// receiver was dropped, are handled by the [`Unclaimed`] policy.
+pub struct Demux<E> {
+    receiver: Receiver<E>,
+    routes: Vec<Option<Route<E>>>,
+    unclaimed: Unclaimed<E>,
+}
+
+impl<E: Variants + 'static> Demux<E> {
+    /// Creates a demultiplexer for the values received on the channel.
+    pub fn new(receiver: Receiver<E>, unclaimed: Unclaimed<E>) -> Self {
+        Demux {
+            receiver,
+            routes: empty_slots::<E, _>(),
+            unclaimed,
+        }
+    }
+
+    /// Hands out a receiver for the values of the variant holding a
+    /// `T`. Subscribing to a type again disconnects the previous
+    /// receiver for it.
+    pub fn subscribe<T>(&mut self) -> Receiver<T>
+    where
+        E: ContainsVariant<T> + From<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+        T: Send + 'static,
+    {
+        let (sender, receiver): (Sender<T>, Receiver<T>) = channel();
+        self.routes[slot_of::<E, T>()] = Some(Box::new(move |value: E| {
+            let value = value
+                .get_variant()
+                .unwrap_or_else(|_| unreachable!("Values are routed by their variant."));
+            sender.send(value).map_err(|error| E::from(error.0))
+        }));
+        receiver
+    }
+
+    /// Sends the value on the channel of its type, or handles it
+    /// according to the policy for unclaimed values.
+    pub fn route(&mut self, value: E) -> Result<(), DemuxError<E>> {
+        let index = value.variant_index();
+        let value = match self.routes[index].as_mut() {
+            Some(route) => match route(value) {
+                Ok(()) => return Ok(()),
+                Err(value) => {
+                    // The receiver was dropped.
+                    self.routes[index] = None;
+                    value
+                }
+            },
+            None => value,
+        };
+        match &mut self.unclaimed {
+            Unclaimed::Drop => Ok(()),
+            Unclaimed::Handle(handler) => {
+                handler(value);
+                Ok(())
+            }
+            Unclaimed::Error => Err(DemuxError {
+                variant: value.variant_name(),
+                value,
+            }),
+        }
+    }
+
+    /// Routes the values received on the channel until all of
+    /// its senders are dropped, which disconnects the receivers
+    /// handed out.
+    pub fn run(mut self) -> Result<(), DemuxError<E>> {
+        while let Ok(value) = self.receiver.recv() {
+            self.route(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> fmt::Debug for Demux<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Demux")
+            .field(
+                "routes",
+                &self.routes.iter().filter(|route| route.is_some()).count(),
+            )
+            .field("unclaimed", &self.unclaimed)
+            .finish_non_exhaustive()
+    }
+}
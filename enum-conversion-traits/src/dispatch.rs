@@ -0,0 +1,130 @@
+//! Calling a handler registered for the type held by the
+//! active variant of an enum.
+
+use std::fmt;
+
+use crate::{empty_slots, slot_of, ContainsVariant, MarkerIndex, Variants};
+
+/// Called with the enum whose active variant it was registered for.
+type Handler<'h, E> = Box<dyn FnMut(E) + 'h>;
+
+/// Holds a handler for each variant of an enum `E`, registered by the
+/// type the variant holds, in place of a `match` over the enum, e.g.
+/// ```ignore
+/// let mut dispatcher = Dispatcher::new()
+///     .on(|click: u32| clicks += click)
+///     .on_ref(|key: &char| print!("{}", key));
+/// dispatcher.dispatch(Event::Key('a'))?;
+/// ```
+/// The handlers are stored in slots indexed by the variant,
+/// so dispatching takes constant time.
+pub struct Dispatcher<'h, E> {
+    handlers: Vec<Option<Handler<'h, E>>>,
+}
+
+impl<'h, E: Variants> Dispatcher<'h, E> {
+    /// Creates a dispatcher without any handlers.
+    pub fn new() -> Self {
+        Dispatcher {
+            handlers: empty_slots::<E, _>(),
+        }
+    }
+}
+
+impl<'h, E: 'h> Dispatcher<'h, E> {
+    /// Registers a handler for the values of the variant holding a `T`,
+    /// replacing any handler previously registered for it.
+    pub fn on<T, F>(mut self, mut handler: F) -> Self
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+        F: FnMut(T) + 'h,
+    {
+        self.handlers[slot_of::<E, T>()] = Some(Box::new(move |value: E| {
+            handler(
+                value
+                    .get_variant()
+                    .unwrap_or_else(|_| unreachable!("Values are dispatched by their variant.")),
+            )
+        }));
+        self
+    }
+
+    /// Registers a handler borrowing the values of the variant holding
+    /// a `T`, replacing any handler previously registered for it.
+    pub fn on_ref<T, F>(mut self, mut handler: F) -> Self
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+        F: FnMut(&T) + 'h,
+    {
+        self.handlers[slot_of::<E, T>()] = Some(Box::new(move |value: E| {
+            handler(
+                value
+                    .get_variant_ref()
+                    .unwrap_or_else(|_| unreachable!("Values are dispatched by their variant.")),
+            )
+        }));
+        self
+    }
+
+    /// Checks if a handler is registered for the variant holding a `T`.
+    pub fn handles<T>(&self) -> bool
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+    {
+        self.handlers[slot_of::<E, T>()].is_some()
+    }
+}
+
+impl<'h, E: Variants> Dispatcher<'h, E> {
+    /// Calls the handler registered for the active variant,
+    /// or gives the value back if there is none.
+    pub fn dispatch(&mut self, value: E) -> Result<(), E> {
+        match self.handlers[value.variant_index()].as_mut() {
+            Some(handler) => {
+                handler(value);
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// The names of the variants without a handler,
+    /// in the order they are declared.
+    pub fn unhandled(&self) -> Vec<&'static str> {
+        self.handlers
+            .iter()
+            .zip(E::VARIANT_NAMES)
+            .filter(|(handler, _)| handler.is_none())
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    /// Panics with the names of the variants without a handler, if any.
+    pub fn assert_exhaustive(&self) {
+        let unhandled = self.unhandled();
+        if !unhandled.is_empty() {
+            panic!(
+                "Dispatcher for <{}> has no handler for variants: {}",
+                std::any::type_name::<E>(),
+                unhandled.join(", "),
+            );
+        }
+    }
+}
+
+impl<'h, E: Variants> Default for Dispatcher<'h, E> {
+    fn default() -> Self {
+        Dispatcher::new()
+    }
+}
+
+impl<'h, E: Variants> fmt::Debug for Dispatcher<'h, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field("unhandled", &self.unhandled())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,127 @@
+//! Adapters for futures resolving to an enum, converting the
+//! enum into the value of one of its variants.
+//!
+//! These only rely on [`std::future::Future`], so they work
+//! under any executor.
+
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{ContainsVariant, GetVariant, TryTo, Variants};
+
+/// Extends futures resolving to an enum with adapters
+/// converting the enum into the value of a variant, e.g.
+/// ```ignore
+/// let click: u32 = recv().expect_variant::<u32>().await;
+/// ```
+pub trait VariantFuture: Future + Sized {
+    /// Resolves to the value of type `T` held by the enum,
+    /// or gives back the enum if it holds another variant.
+    fn variant<T>(self) -> IntoVariant<Self, T>
+    where
+        Self::Output: ContainsVariant<T>,
+    {
+        IntoVariant {
+            future: self,
+            _variant: PhantomData,
+        }
+    }
+
+    /// Resolves to the value of type `T` held by the enum.
+    ///
+    /// # Panics
+    /// When polled, if the enum holds another variant, with the
+    /// error of its [`TryTo`] conversion.
+    fn expect_variant<T>(self) -> ExpectVariant<Self, T>
+    where
+        Self::Output: TryTo<T>,
+        <Self::Output as TryTo<T>>::Error: fmt::Debug,
+    {
+        ExpectVariant {
+            future: self,
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<F> VariantFuture for F
+where
+    F: Future,
+    F::Output: Variants,
+{
+}
+
+/// The future returned by [`VariantFuture::variant`].
+#[must_use = "futures do nothing unless polled"]
+pub struct IntoVariant<F, T> {
+    future: F,
+    _variant: PhantomData<fn() -> T>,
+}
+
+impl<F, T> Future for IntoVariant<F, T>
+where
+    F: Future,
+    F::Output: ContainsVariant<T>,
+{
+    type Output = Result<T, F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: The future is pinned whenever the adapter is, as it
+        // is never moved out of the adapter, which does not implement
+        // `Drop` and is only `Unpin` if the future is.
+        let future = unsafe { self.map_unchecked_mut(|adapter| &mut adapter.future) };
+        future.poll(cx).map(|value| {
+            if value.get_variant_ref().is_err() {
+                return Err(value);
+            }
+            Ok(value
+                .get_variant()
+                .unwrap_or_else(|_| unreachable!("The active variant was checked.")))
+        })
+    }
+}
+
+impl<F: fmt::Debug, T> fmt::Debug for IntoVariant<F, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IntoVariant")
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+/// The future returned by [`VariantFuture::expect_variant`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ExpectVariant<F, T> {
+    future: F,
+    _variant: PhantomData<fn() -> T>,
+}
+
+impl<F, T> Future for ExpectVariant<F, T>
+where
+    F: Future,
+    F::Output: TryTo<T>,
+    <F::Output as TryTo<T>>::Error: fmt::Debug,
+{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: As for `IntoVariant`.
+        let future = unsafe { self.map_unchecked_mut(|adapter| &mut adapter.future) };
+        future.poll(cx).map(|value| {
+            value
+                .try_to()
+                .unwrap_or_else(|error| panic!("Future resolved to another variant: {:?}", error))
+        })
+    }
+}
+
+impl<F: fmt::Debug, T> fmt::Debug for ExpectVariant<F, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExpectVariant")
+            .field("future", &self.future)
+            .finish()
+    }
+}
@@ -0,0 +1,133 @@
+//! Iterator adapters keeping only the values of the variants
+//! holding a given type.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::matching::{MatchVariant, Mut, Owned, Ref};
+use crate::Variants;
+
+/// Extends iterators over an enum, references to an enum or mutable
+/// references to an enum with adapters selecting a variant by type.
+///
+/// The `Mode` is one of [`Owned`], [`Ref`] or [`Mut`] and is
+/// inferred from the items, so only the type needs to be given, e.g.
+/// `values.iter().count_of::<String>()`.
+pub trait VariantIterator<Mode>: Iterator + Sized {
+    /// Keeps the values of type `T` of an iterator over enums.
+    fn variants_of<T>(self) -> OfVariant<Self, T, Owned>
+    where
+        Self::Item: MatchVariant<T, Owned>,
+    {
+        OfVariant::new(self)
+    }
+
+    /// Keeps references to the values of type `T` of an
+    /// iterator over references to enums.
+    fn refs_of<T>(self) -> OfVariant<Self, T, Ref>
+    where
+        Self::Item: MatchVariant<T, Ref>,
+    {
+        OfVariant::new(self)
+    }
+
+    /// Keeps mutable references to the values of type `T` of
+    /// an iterator over mutable references to enums.
+    fn muts_of<T>(self) -> OfVariant<Self, T, Mut>
+    where
+        Self::Item: MatchVariant<T, Mut>,
+    {
+        OfVariant::new(self)
+    }
+
+    /// Counts the items whose active variant holds a `T`.
+    fn count_of<T>(self) -> usize
+    where
+        Self::Item: MatchVariant<T, Mode>,
+    {
+        OfVariant::<Self, T, Mode>::new(self).count()
+    }
+}
+
+// The implementations are told apart by the items of the iterator,
+// which is what allows the `Mode` to be inferred. Only enums implement
+// `Variants`, so the first does not apply to iterators over references.
+impl<I, E> VariantIterator<Owned> for I
+where
+    I: Iterator<Item = E>,
+    E: Variants,
+{
+}
+
+impl<'a, I, E> VariantIterator<Ref> for I
+where
+    I: Iterator<Item = &'a E>,
+    E: Variants + 'a,
+{
+}
+
+impl<'a, I, E> VariantIterator<Mut> for I
+where
+    I: Iterator<Item = &'a mut E>,
+    E: Variants + 'a,
+{
+}
+
+/// The iterator returned by the adapters of [`VariantIterator`].
+/// Items whose active variant does not hold a `T` are skipped.
+pub struct OfVariant<I, T, Mode> {
+    iter: I,
+    _variant: PhantomData<fn() -> (T, Mode)>,
+}
+
+impl<I, T, Mode> OfVariant<I, T, Mode> {
+    fn new(iter: I) -> Self {
+        OfVariant {
+            iter,
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<I, T, Mode> Iterator for OfVariant<I, T, Mode>
+where
+    I: Iterator,
+    I::Item: MatchVariant<T, Mode>,
+{
+    type Item = <I::Item as MatchVariant<T, Mode>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_map(|value| value.match_variant().ok())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I, T, Mode> DoubleEndedIterator for OfVariant<I, T, Mode>
+where
+    I: DoubleEndedIterator,
+    I::Item: MatchVariant<T, Mode>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .by_ref()
+            .rev()
+            .find_map(|value| value.match_variant().ok())
+    }
+}
+
+impl<I: Clone, T, Mode> Clone for OfVariant<I, T, Mode> {
+    fn clone(&self) -> Self {
+        OfVariant::new(self.iter.clone())
+    }
+}
+
+impl<I: fmt::Debug, T, Mode> fmt::Debug for OfVariant<I, T, Mode> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OfVariant")
+            .field("iter", &self.iter)
+            .finish()
+    }
+}
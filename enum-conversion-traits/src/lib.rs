@@ -1,5 +1,27 @@
 use std::{error::Error, fmt};
 
+pub mod cell;
+pub mod demux;
+pub mod dispatch;
+pub mod future;
+pub mod iter;
+pub mod map;
+pub mod matching;
+pub mod shared;
+pub mod transition;
+pub mod vec;
+
+pub use cell::{ref_mut_variant, ref_variant};
+pub use demux::{Demux, DemuxError, Unclaimed};
+pub use dispatch::Dispatcher;
+pub use future::VariantFuture;
+pub use iter::VariantIterator;
+pub use map::VariantMap;
+pub use matching::MatchVariant;
+pub use shared::{ArcVariant, RcVariant};
+pub use transition::{AllowedTransition, TransitionVariant};
+pub use vec::{all_refs, VecConversionError};
+
 /// Custom errors for this crate. Keeps a record of
 /// the enum and requested type that produced the error
 #[derive(Debug)]
@@ -29,6 +51,35 @@ impl fmt::Display for EnumConversionError {
     }
 }
 
+/// The error returned when parsing the name of a variant
+/// into a kind generated by `#[DeriveKind]` fails.
+#[derive(Debug)]
+pub struct ParseKindError {
+    pub kind: String,
+    pub input: String,
+}
+
+impl ParseKindError {
+    pub fn new(kind: &str, input: &str) -> ParseKindError {
+        ParseKindError {
+            kind: kind.to_string(),
+            input: input.to_string(),
+        }
+    }
+}
+
+impl Error for ParseKindError {}
+
+impl fmt::Display for ParseKindError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ParseKindError :: <{}> is not a variant of <{}>",
+            self.input, self.kind,
+        )
+    }
+}
+
 /// This is a helper trait for implementing the [`TryTo`] and
 /// [`std::convert::TryFrom`] traits on enums. Is uses marker structs
 /// to uniquely identify a type in the enum. This avoids
@@ -40,6 +91,53 @@ pub trait GetVariant<T, Marker> {
     fn get_variant_mut(&mut self) -> Result<&mut T, EnumConversionError>;
 }
 
+/// Names the marker type identifying the variant of
+/// an enum that holds a `T`.
+///
+/// This allows code that is generic over enums to require
+/// a variant of type `T` without knowing its marker, e.g.
+/// ```
+/// use enum_conversion_traits::ContainsVariant;
+///
+/// fn get<E: ContainsVariant<T>, T>(value: &E) -> Option<&T> {
+///     value.get_variant_ref().ok()
+/// }
+/// ```
+pub trait ContainsVariant<T>: GetVariant<T, <Self as ContainsVariant<T>>::Marker> {
+    type Marker;
+}
+
+/// Implemented by the markers of every enum, giving the position
+/// of the variant the marker identifies, as in [`Variants::VARIANT_NAMES`].
+pub trait MarkerIndex {
+    const INDEX: usize;
+}
+
+/// Implemented by the markers of enums with `#[DeriveKind]`,
+/// giving the kind of the variant the marker identifies.
+pub trait MarkerKind<K> {
+    const KIND: K;
+}
+
+/// Implemented by the buckets generated by `#[DeriveBuckets]`
+/// once for the type held by each variant, giving the values
+/// of that variant in the order they were added.
+pub trait Bucket<T> {
+    fn get_bucket(&self) -> &[T];
+    fn get_bucket_mut(&mut self) -> &mut [T];
+}
+
+/// The type `T`, named through a projection involving the lifetime
+/// `'a` and the types `P`.
+///
+/// The code generated for an enum bounds the types of its variants
+/// through this alias, so that a bound that does not hold, such as
+/// `String: Copy`, only makes the item it is placed on unavailable.
+/// Without any generic parameters, such a bound would be an error.
+#[doc(hidden)]
+pub type Deferred<'a, T, P = ()> =
+    <Result<T, std::marker::PhantomData<(&'a (), P)>> as IntoIterator>::Item;
+
 /// Not all enums can have the [`std::convert::TryFrom`] trait derived
 /// on them because of rules around implementing foreign traits on
 /// foreign types.
@@ -50,3 +148,45 @@ pub trait TryTo<T> {
     type Error;
     fn try_to(self) -> Result<T, Self::Error>;
 }
+
+/// Describes the variants of an enum. This is implemented
+/// for every enum with the `EnumConversions` macro.
+///
+/// Variants removed by a `#[cfg]` attribute do not count.
+/// The remaining variants are numbered in declaration order.
+pub trait Variants {
+    /// The number of variants of the enum.
+    const VARIANT_COUNT: usize;
+    /// The names of the variants, in declaration order.
+    const VARIANT_NAMES: &'static [&'static str];
+    /// The types held by the variants, as written in the enum.
+    const VARIANT_TYPE_NAMES: &'static [&'static str];
+
+    /// The position of the active variant in [`Variants::VARIANT_NAMES`].
+    fn variant_index(&self) -> usize;
+
+    /// The name of the active variant.
+    fn variant_name(&self) -> &'static str {
+        Self::VARIANT_NAMES[self.variant_index()]
+    }
+
+    /// Checks if both values have the same active variant,
+    /// regardless of the values they hold.
+    fn same_variant(&self, other: &Self) -> bool {
+        self.variant_index() == other.variant_index()
+    }
+}
+
+/// The position of the variant of `E` holding a `T`, as given by its marker.
+pub(crate) fn slot_of<E, T>() -> usize
+where
+    E: ContainsVariant<T>,
+    <E as ContainsVariant<T>>::Marker: MarkerIndex,
+{
+    <<E as ContainsVariant<T>>::Marker as MarkerIndex>::INDEX
+}
+
+/// An empty slot for each variant of `E`, indexed as by [`slot_of`].
+pub(crate) fn empty_slots<E: Variants, X>() -> Vec<Option<X>> {
+    (0..E::VARIANT_COUNT).map(|_| None).collect()
+}
@@ -0,0 +1,164 @@
+//! A map holding at most one value per variant of an enum,
+//! keyed by the type the variant holds.
+
+use std::fmt;
+
+use crate::{empty_slots, slot_of, ContainsVariant, MarkerIndex, Variants};
+
+/// Stores at most one value for each variant of an enum `E`, in a
+/// slot indexed by the variant. Values are looked up by their type
+/// through the markers of the enum, so unlike a map keyed by
+/// [`std::any::TypeId`], the types need not be `'static`.
+pub struct VariantMap<E> {
+    slots: Box<[Option<E>]>,
+}
+
+impl<E: Variants> VariantMap<E> {
+    /// Creates a map without any values.
+    pub fn new() -> Self {
+        VariantMap {
+            slots: empty_slots::<E, E>().into_boxed_slice(),
+        }
+    }
+
+    /// Stores the value in the slot of its variant, returning
+    /// the value previously stored there, if any.
+    pub fn insert(&mut self, value: impl Into<E>) -> Option<E> {
+        let value = value.into();
+        let index = value.variant_index();
+        self.slots[index].replace(value)
+    }
+}
+
+impl<E> VariantMap<E> {
+    /// The value of type `T`, if one is stored.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+    {
+        self.slots[slot_of::<E, T>()]
+            .as_ref()
+            .and_then(|value| value.get_variant_ref().ok())
+    }
+
+    /// Mutably borrows the value of type `T`, if one is stored.
+    pub fn get_mut<T>(&mut self) -> Option<&mut T>
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+    {
+        self.slots[slot_of::<E, T>()]
+            .as_mut()
+            .and_then(|value| value.get_variant_mut().ok())
+    }
+
+    /// Takes the value of type `T` out of the map, if one is stored.
+    pub fn remove<T>(&mut self) -> Option<T>
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+    {
+        self.slots[slot_of::<E, T>()]
+            .take()
+            .and_then(|value| value.get_variant().ok())
+    }
+
+    /// Checks if a value of type `T` is stored.
+    pub fn contains<T>(&self) -> bool
+    where
+        E: ContainsVariant<T>,
+        <E as ContainsVariant<T>>::Marker: MarkerIndex,
+    {
+        self.slots[slot_of::<E, T>()].is_some()
+    }
+
+    /// The number of values stored.
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Checks if no values are stored.
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(|slot| slot.is_none())
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+    }
+
+    /// Iterates over the values, in the order the variants are declared.
+    pub fn iter(&self) -> std::iter::Flatten<std::slice::Iter<'_, Option<E>>> {
+        self.slots.iter().flatten()
+    }
+
+    /// Iterates mutably over the values, in the order the
+    /// variants are declared.
+    pub fn iter_mut(&mut self) -> std::iter::Flatten<std::slice::IterMut<'_, Option<E>>> {
+        self.slots.iter_mut().flatten()
+    }
+}
+
+impl<E: Variants> Default for VariantMap<E> {
+    fn default() -> Self {
+        VariantMap::new()
+    }
+}
+
+impl<E: Clone> Clone for VariantMap<E> {
+    fn clone(&self) -> Self {
+        VariantMap {
+            slots: self.slots.clone(),
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for VariantMap<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<E: Variants> Extend<E> for VariantMap<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<E: Variants> FromIterator<E> for VariantMap<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        let mut map = VariantMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<E> IntoIterator for VariantMap<E> {
+    type Item = E;
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Option<E>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.slots.into_vec().into_iter().flatten()
+    }
+}
+
+impl<'a, E> IntoIterator for &'a VariantMap<E> {
+    type Item = &'a E;
+    type IntoIter = std::iter::Flatten<std::slice::Iter<'a, Option<E>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, E> IntoIterator for &'a mut VariantMap<E> {
+    type Item = &'a mut E;
+    type IntoIter = std::iter::Flatten<std::slice::IterMut<'a, Option<E>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
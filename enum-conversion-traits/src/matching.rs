@@ -0,0 +1,78 @@
+//! Support for the `match_variant!` macro, which branches
+//! on the type held by an enum rather than its variants.
+
+use crate::ContainsVariant;
+
+/// The value is matched by value.
+pub enum Owned {}
+/// The value is matched by reference.
+pub enum Ref {}
+/// The value is matched by mutable reference.
+pub enum Mut {}
+
+/// Takes the value of type `T` out of an enum, a reference to
+/// an enum or a mutable reference to an enum. The `Mode` is one
+/// of [`Owned`], [`Ref`] or [`Mut`] and is inferred, so that the
+/// implementations for the three cases do not overlap.
+#[diagnostic::on_unimplemented(message = "`{Self}` has no variant holding a `{T}`")]
+pub trait MatchVariant<T, Mode>: Sized {
+    type Output;
+
+    /// Returns the value of the variant holding a `T`, or
+    /// gives back the input if a different variant is active.
+    fn match_variant(self) -> Result<Self::Output, Self>;
+}
+
+impl<E, T> MatchVariant<T, Owned> for E
+where
+    E: ContainsVariant<T>,
+{
+    type Output = T;
+
+    fn match_variant(self) -> Result<T, Self> {
+        if self.get_variant_ref().is_ok() {
+            Ok(self
+                .get_variant()
+                .unwrap_or_else(|_| unreachable!("The active variant holds the type.")))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<'a, E, T: 'a> MatchVariant<T, Ref> for &'a E
+where
+    E: ContainsVariant<T>,
+{
+    type Output = &'a T;
+
+    fn match_variant(self) -> Result<&'a T, Self> {
+        self.get_variant_ref().map_err(|_| self)
+    }
+}
+
+impl<'a, E, T: 'a> MatchVariant<T, Mut> for &'a mut E
+where
+    E: ContainsVariant<T>,
+{
+    type Output = &'a mut T;
+
+    fn match_variant(self) -> Result<&'a mut T, Self> {
+        // Checking first keeps the mutable borrow from
+        // extending into the error case.
+        if self.get_variant_ref().is_ok() {
+            Ok(self
+                .get_variant_mut()
+                .unwrap_or_else(|_| unreachable!("The active variant holds the type.")))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Calls `f` with the value. Used by `match_variant!` so that
+/// the types of the arguments of closures are inferred from
+/// the value.
+pub fn apply<A, R>(f: impl FnOnce(A) -> R, value: A) -> R {
+    f(value)
+}
@@ -0,0 +1,113 @@
+//! Handles to the value of a variant of an enum behind
+//! an [`Arc`] or [`Rc`], which keep the enum alive.
+
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{ContainsVariant, TryTo};
+
+/// Defines a handle for a shared pointer type. The handle
+/// checks the variant once on creation and stores a pointer
+/// to its value, so that dereferencing is free.
+macro_rules! shared_variant {
+    ($(#[$doc:meta])* $handle:ident, $pointer:ident) => {
+        $(#[$doc])*
+        pub struct $handle<E, T> {
+            owner: $pointer<E>,
+            value: *const T,
+        }
+
+        impl<E, T> $handle<E, T>
+        where
+            E: ContainsVariant<T>,
+        {
+            /// Creates a handle to the value of type `T` in the
+            /// enum, or gives back the pointer if a different
+            /// variant is active.
+            pub fn try_new(owner: $pointer<E>) -> Result<Self, $pointer<E>> {
+                let value = match owner.get_variant_ref() {
+                    Ok(value) => value as *const T,
+                    Err(_) => return Err(owner),
+                };
+                Ok($handle { owner, value })
+            }
+        }
+
+        impl<E, T> $handle<E, T> {
+            /// The pointer to the enum holding the value.
+            pub fn owner(this: &Self) -> &$pointer<E> {
+                &this.owner
+            }
+
+            /// Gives back the pointer to the enum.
+            pub fn into_owner(this: Self) -> $pointer<E> {
+                this.owner
+            }
+        }
+
+        impl<E, T> Deref for $handle<E, T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                // SAFETY: `value` points into the enum owned by `owner`,
+                // which lives as long as this handle. The enum can only
+                // be reached through shared references while the pointer
+                // is shared, so its active variant cannot change and the
+                // value is neither moved nor dropped.
+                unsafe { &*self.value }
+            }
+        }
+
+        impl<E, T> Clone for $handle<E, T> {
+            fn clone(&self) -> Self {
+                $handle {
+                    owner: self.owner.clone(),
+                    value: self.value,
+                }
+            }
+        }
+
+        impl<E, T: fmt::Debug> fmt::Debug for $handle<E, T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple(stringify!($handle)).field(&**self).finish()
+            }
+        }
+
+        impl<E, T> TryTo<$handle<E, T>> for $pointer<E>
+        where
+            E: ContainsVariant<T>,
+        {
+            type Error = $pointer<E>;
+
+            fn try_to(self) -> Result<$handle<E, T>, Self::Error> {
+                $handle::try_new(self)
+            }
+        }
+    };
+}
+
+shared_variant!(
+    /// The value of type `T` in an enum `E` behind an [`Arc`].
+    ///
+    /// The handle keeps the enum alive and dereferences to the
+    /// value without checking the variant again.
+    ArcVariant,
+    Arc
+);
+
+shared_variant!(
+    /// The value of type `T` in an enum `E` behind an [`Rc`].
+    ///
+    /// The handle keeps the enum alive and dereferences to the
+    /// value without checking the variant again.
+    RcVariant,
+    Rc
+);
+
+// SAFETY: The handle gives shared access to the value from any
+// thread holding it, so `T` must be `Sync`. Sending or sharing
+// the `Arc` requires the enum to be `Send` and `Sync`.
+unsafe impl<E: Send + Sync, T: Sync> Send for ArcVariant<E, T> {}
+unsafe impl<E: Send + Sync, T: Sync> Sync for ArcVariant<E, T> {}
@@ -0,0 +1,67 @@
+//! Replacing the active variant of an enum in place with
+//! a value computed from the one it holds.
+
+use std::ptr;
+
+use crate::{ContainsVariant, EnumConversionError, Variants};
+
+/// Implemented by enums for each pair of types their variants
+/// may transition between with [`TransitionVariant::transition`].
+///
+/// The `EnumConversions` macro implements it for every pair,
+/// unless the enum declares the allowed transitions with
+/// `#[Transitions(Old => New, ..)]`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not allow transitions from `{Old}` to `{New}`",
+    note = "the allowed transitions are declared by `#[Transitions]` on the enum"
+)]
+pub trait AllowedTransition<Old, New> {}
+
+/// Aborts the process if dropped while unwinding from a panic,
+/// as the enum then holds a value that was moved out of it.
+struct AbortOnPanic;
+
+impl Drop for AbortOnPanic {
+    fn drop(&mut self) {
+        std::process::abort();
+    }
+}
+
+/// Changes the active variant of an enum through a mutable
+/// reference, without requiring a placeholder value such as
+/// a [`Default`] to swap in while the new value is computed.
+pub trait TransitionVariant: Sized {
+    /// Replaces the value of type `Old` held by the enum by the value
+    /// of type `New` computed from it, e.g.
+    /// ```ignore
+    /// state.transition::<Connecting, Open>(|connecting| connecting.open())?;
+    /// ```
+    /// Returns an error, leaving the enum untouched, if the active
+    /// variant does not hold an `Old`.
+    ///
+    /// As the enum holds no valid value while `f` runs,
+    /// the process is aborted if `f` panics.
+    fn transition<Old, New>(
+        &mut self,
+        f: impl FnOnce(Old) -> New,
+    ) -> Result<(), EnumConversionError>
+    where
+        Self: ContainsVariant<Old> + From<New> + AllowedTransition<Old, New>,
+    {
+        self.get_variant_ref()?;
+        let guard = AbortOnPanic;
+        // SAFETY: The value read is written back before the
+        // guard is forgotten, so it is never dropped twice or
+        // observed after being moved, even if `f` panics.
+        unsafe {
+            let old: Old = ptr::read(self)
+                .get_variant()
+                .unwrap_or_else(|_| unreachable!("The active variant was checked."));
+            ptr::write(self, Self::from(f(old)));
+        }
+        std::mem::forget(guard);
+        Ok(())
+    }
+}
+
+impl<E: Variants> TransitionVariant for E {}
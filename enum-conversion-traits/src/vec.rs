@@ -0,0 +1,83 @@
+//! Conversions of whole collections of enums into the values of
+//! one of their variants, which succeed only if every element
+//! holds a value of that type.
+
+use std::{error::Error, fmt};
+
+use crate::{slot_of, ContainsVariant, MarkerIndex, TryTo, Variants};
+
+/// The error returned when converting a `Vec` of enums into a `Vec`
+/// of the values of one variant fails. Gives back the elements,
+/// along with the position of the first one holding another variant.
+pub struct VecConversionError<E> {
+    pub index: usize,
+    pub values: Vec<E>,
+}
+
+impl<E> fmt::Debug for VecConversionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VecConversionError")
+            .field("index", &self.index)
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+impl<E> Error for VecConversionError<E> {}
+
+impl<E> fmt::Display for VecConversionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "VecConversionError :: Element {} of type <{}> holds a different variant",
+            self.index,
+            std::any::type_name::<E>(),
+        )
+    }
+}
+
+/// Every element is checked by its variant index before any is moved,
+/// so that they can be given back on failure. The values are then
+/// collected in place, so the allocation of the `Vec` is reused if
+/// the enum and `T` have the same size and alignment.
+impl<E, T> TryTo<Vec<T>> for Vec<E>
+where
+    E: ContainsVariant<T> + Variants,
+    <E as ContainsVariant<T>>::Marker: MarkerIndex,
+{
+    type Error = VecConversionError<E>;
+
+    fn try_to(self) -> Result<Vec<T>, Self::Error> {
+        let index = slot_of::<E, T>();
+        let mismatch = self.iter().position(|value| value.variant_index() != index);
+        if let Some(position) = mismatch {
+            return Err(VecConversionError {
+                index: position,
+                values: self,
+            });
+        }
+        Ok(self
+            .into_iter()
+            .map(|value| {
+                value
+                    .get_variant()
+                    .unwrap_or_else(|_| unreachable!("Every element holds the type."))
+            })
+            .collect())
+    }
+}
+
+/// Borrows the values of type `T` of a slice of enums, or returns
+/// `None` if any element holds a different variant.
+///
+/// As with [`crate::ref_variant`], this is not a method, so that
+/// it cannot shadow the methods of slices.
+pub fn all_refs<E, T>(values: &[E]) -> Option<Vec<&T>>
+where
+    E: ContainsVariant<T>,
+{
+    values
+        .iter()
+        .map(|value| value.get_variant_ref().ok())
+        .collect()
+}
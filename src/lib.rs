@@ -1,4 +1,51 @@
 pub mod prelude {
-    pub use enum_conversion_derive::{DeriveTryFrom, EnumConversions};
+    pub use crate::match_variant;
+    pub use enum_conversion_derive::{
+        DeriveBuckets, DeriveKind, DerivePin, DeriveTryFrom, DeriveVariantSet, DeriveViews,
+        DeriveVisitor, EnumConversions, Transitions,
+    };
     pub use enum_conversion_traits::*;
 }
+
+/// Branches on the type held by an enum, without naming its
+/// variants. Each arm gives a type and a function called with
+/// the value of that type. The last arm must be `_ => expr`,
+/// which is evaluated if none of the types match.
+///
+/// The enum can be given by value, by reference or by mutable
+/// reference, and the functions receive the value in the same
+/// way. Listing a type that is not held by a variant is a
+/// compile error.
+///
+/// # Example
+/// ```
+/// use enum_conversion::prelude::*;
+///
+/// #[EnumConversions]
+/// enum Enum {
+///     F1(i32),
+///     F2(String),
+///     F3(bool),
+/// }
+///
+/// let value: Enum = String::from("hello").into();
+/// let length = match_variant!(&value,
+///     i32 => |_| 0,
+///     String => |s| s.len(),
+///     _ => 1,
+/// );
+/// assert_eq!(length, 5);
+/// ```
+#[macro_export]
+macro_rules! match_variant {
+    ($value:expr, _ => $default:expr $(,)?) => {{
+        let _ = $value;
+        $default
+    }};
+    ($value:expr, $ty:ty => $arm:expr, $($rest:tt)+) => {
+        match $crate::prelude::MatchVariant::<$ty, _>::match_variant($value) {
+            ::std::result::Result::Ok(inner) => $crate::prelude::matching::apply($arm, inner),
+            ::std::result::Result::Err(value) => $crate::match_variant!(value, $($rest)+),
+        }
+    };
+}
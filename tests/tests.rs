@@ -64,6 +64,360 @@ mod test_derive_basic {
     }
 }
 
+/// Test enums with combinations of lifetimes, type and
+/// const generics, defaults and where clauses.
+#[cfg(test)]
+mod test_derive_generics {
+    use enum_conversion::prelude::*;
+    use std::convert::TryFrom;
+    use std::fmt::Debug;
+
+    /// Const generics.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveTryFrom(auto)]
+    enum Array<T, const N: usize> {
+        Items([T; N]),
+        Flag(bool),
+    }
+
+    /// Default type and const parameters.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveTryFrom]
+    enum Value<T = String, const N: usize = 4> {
+        Text(Vec<T>),
+        Bytes([u8; N]),
+    }
+
+    /// A defaulted const parameter only.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveTryFrom]
+    #[DeriveViews]
+    enum Padded<const N: usize = 3> {
+        Bytes([u8; N]),
+        Flag(bool),
+    }
+
+    /// Lifetime bounds, defaults and a where clause.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Borrowed<'a, 'b: 'a, T: 'a = i32, const N: usize = 2>
+    where
+        T: Debug,
+    {
+        Slice(&'a [T]),
+        Pair(&'b [u8; N]),
+    }
+
+    /// A type that is not `Copy`.
+    #[derive(Debug, PartialEq)]
+    struct Local;
+
+    /// The bound rules out `T = Local`, so the variants never collide.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Bounded<T: Copy> {
+        Value(T),
+        Local(Local),
+    }
+
+    #[test]
+    fn test_const_generics() {
+        let array: Array<i32, 2> = [1, 2].into();
+        let items: &[i32; 2] = (&array).try_to().expect("Test failed");
+        assert_eq!(items, &[1, 2]);
+        let flag: Result<bool, EnumConversionError> = bool::try_from(array);
+        assert!(flag.is_err());
+    }
+
+    #[test]
+    fn test_defaults() {
+        let mut value: Value = vec![String::from("a")].into();
+        let text: &mut Vec<String> = (&mut value).try_into().expect("Test failed");
+        text.push(String::from("b"));
+        assert_eq!(value, Value::Text(vec!["a".into(), "b".into()]));
+        let value = Value::<bool, 1>::from([7]);
+        let bytes = <[u8; 1]>::try_from(value).expect("Test failed");
+        assert_eq!(bytes, [7]);
+    }
+
+    #[test]
+    fn test_bounded_collision() {
+        let value: Bounded<u8> = 1.into();
+        assert_eq!(value, Bounded::Value(1));
+        let local: Bounded<u8> = Local.into();
+        let local: Local = local.try_to().expect("Test failed");
+        assert_eq!(local, Local);
+    }
+
+    #[test]
+    fn test_const_default() {
+        let padded: Padded = [1, 2, 3].into();
+        assert_eq!(padded.as_ref_enum().to_owned_enum(), padded);
+        let bytes = <[u8; 3]>::try_from(padded).expect("Test failed");
+        assert_eq!(bytes, [1, 2, 3]);
+        let padded = Padded::<1>::from(true);
+        assert!(bool::try_from(padded).expect("Test failed"));
+    }
+
+    #[test]
+    fn test_bounds_and_where_clause() {
+        let items = [1, 2];
+        let borrowed: Borrowed = (&items[..]).into();
+        let slice: &&[i32] = (&borrowed).try_to().expect("Test failed");
+        assert_eq!(*slice, &[1, 2]);
+        let bytes = [1, 2];
+        let borrowed = Borrowed::<bool>::from(&bytes);
+        let res: &[u8; 2] = borrowed.try_to().expect("Test failed");
+        assert_eq!(res, &[1, 2]);
+    }
+}
+
+/// Test that the markers can be named outside of
+/// the module defining the enum.
+#[cfg(test)]
+mod test_derive_markers {
+    use enum_conversion::prelude::*;
+
+    mod upstream {
+        use enum_conversion::prelude::*;
+
+        #[derive(Debug, PartialEq)]
+        #[EnumConversions]
+        pub enum Event {
+            Number(i32),
+            Text(String),
+        }
+    }
+
+    use upstream::enum___conversion___Event as markers;
+    use upstream::Event;
+
+    fn number<E: GetVariant<i32, markers::Number>>(event: &E) -> Option<&i32> {
+        event.get_variant_ref().ok()
+    }
+
+    fn variant<E: ContainsVariant<T>, T>(event: E) -> Option<T> {
+        event.get_variant().ok()
+    }
+
+    #[test]
+    fn test_named_marker() {
+        let event: Event = 1.into();
+        assert_eq!(number(&event), Some(&1));
+    }
+
+    #[test]
+    fn test_contains_variant() {
+        let event: Event = String::from("text").into();
+        assert_eq!(variant::<_, String>(event), Some(String::from("text")));
+        let event: Event = 1.into();
+        assert_eq!(variant::<_, String>(event), None);
+    }
+}
+
+/// Test that `#[cfg]` and `#[deprecated]` attributes
+/// are respected by the generated items.
+#[cfg(test)]
+mod test_derive_attributes {
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Test {
+        F1(i32),
+        #[cfg(any())]
+        F2(DoesNotExist),
+        #[deprecated(note = "Use F1 instead.")]
+        F3(u8),
+    }
+
+    /// Alternative definitions of the same enum.
+    #[cfg(debug_assertions)]
+    #[EnumConversions]
+    enum Alternative {
+        F1(i32),
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[EnumConversions]
+    enum Alternative {
+        F1(i32),
+        F2(bool),
+    }
+
+    /// Only one of the variants holding an `i64` exists.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Exclusive {
+        #[cfg(debug_assertions)]
+        Native(i64),
+        #[cfg(not(debug_assertions))]
+        Other(i64),
+        Text(String),
+    }
+
+    /// `#[cfg]` attributes inside of `#[cfg_attr]` apply too.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Conditional {
+        F1(i32),
+        #[cfg_attr(test, cfg(any()))]
+        F2(DoesNotExist),
+    }
+
+    /// The views and buckets do not name the types of removed variants.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveViews]
+    #[DeriveBuckets]
+    enum Generated<T> {
+        F1(i32),
+        #[cfg(any())]
+        F2(DoesNotExist<T>),
+        F3(Vec<T>),
+    }
+
+    #[test]
+    fn test_generated_items() {
+        let generated: Generated<u8> = vec![1].into();
+        assert_eq!(generated.as_ref_enum().to_owned_enum(), generated);
+        let buckets: GeneratedBuckets<u8> = vec![generated, 2.into()].into_iter().collect();
+        assert_eq!(buckets.bucket::<i32>(), &[2]);
+        assert_eq!(buckets.bucket::<Vec<u8>>(), &[vec![1]]);
+    }
+
+    #[test]
+    fn test_exclusive_variants() {
+        let exclusive: Exclusive = 1_i64.into();
+        #[cfg(debug_assertions)]
+        assert_eq!(exclusive, Exclusive::Native(1));
+        #[cfg(not(debug_assertions))]
+        assert_eq!(exclusive, Exclusive::Other(1));
+        assert_eq!(Exclusive::VARIANT_COUNT, 2);
+        let conditional: Conditional = 2.into();
+        assert_eq!(conditional, Conditional::F1(2));
+        assert_eq!(Conditional::VARIANT_NAMES, &["F1"]);
+    }
+
+    #[test]
+    fn test_cfg_variant() {
+        let test: Test = 1.into();
+        let int: i32 = test.try_to().expect("Test failed");
+        assert_eq!(int, 1);
+        let alternative: Alternative = 2_i32.into();
+        let int: &i32 = (&alternative).try_to().expect("Test failed");
+        assert_eq!(*int, 2);
+    }
+
+    #[test]
+    fn test_deprecated_variant() {
+        let test: Test = 3_u8.into();
+        let int: Result<u8, _> = test.try_to();
+        assert_eq!(int.expect("Test failed"), 3);
+    }
+}
+
+/// Test that recursive enums referring to
+/// `Self` get the correct conversions.
+#[cfg(test)]
+mod test_derive_recursive {
+    use enum_conversion::prelude::*;
+    use std::convert::TryFrom;
+
+    /// `TryFrom<Tree> for Box<Tree>` is already implemented
+    /// by the standard library, as `Box<Tree>: From<Tree>`,
+    /// so `auto` mode skips the `Node` variant.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveTryFrom(auto)]
+    enum Tree {
+        Leaf(i32),
+        Node(Box<Self>),
+        List(Vec<Self>),
+        Maybe(Option<Box<Self>>),
+    }
+
+    /// A generic recursive enum.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Expr<'a, T> {
+        Value(&'a T),
+        Neg(Box<Self>),
+    }
+
+    #[test]
+    fn test_from() {
+        let tree: Tree = Box::new(Tree::Leaf(1)).into();
+        assert_eq!(tree, Tree::Node(Box::new(Tree::Leaf(1))));
+        let tree: Tree = vec![Tree::Leaf(1)].into();
+        assert_eq!(tree, Tree::List(vec![Tree::Leaf(1)]));
+        let tree: Tree = Some(Box::new(Tree::Leaf(1))).into();
+        assert_eq!(tree, Tree::Maybe(Some(Box::new(Tree::Leaf(1)))));
+    }
+
+    #[test]
+    fn test_try_from() {
+        let tree = Tree::List(vec![Tree::Leaf(2)]);
+        let list = Vec::<Tree>::try_from(tree).expect("Test failed");
+        assert_eq!(list, vec![Tree::Leaf(2)]);
+        let tree = Tree::List(vec![]);
+        assert!(Option::<Box<Tree>>::try_from(tree).is_err());
+        let leaf = i32::try_from(Tree::Leaf(3)).expect("Test failed");
+        assert_eq!(leaf, 3);
+    }
+
+    #[test]
+    fn test_try_to() {
+        let tree = Tree::Node(Box::new(Tree::Leaf(2)));
+        let node: Box<Tree> = tree.try_to().expect("Test failed");
+        assert_eq!(*node, Tree::Leaf(2));
+    }
+
+    #[test]
+    fn test_try_to_generic() {
+        let expr: Expr<'static, i32> = Box::new(Expr::Value(&1)).into();
+        let inner: Box<Expr<i32>> = expr.try_to().expect("Test failed");
+        assert_eq!(*inner, Expr::Value(&1));
+    }
+}
+
+/// Test that `TryFrom` is only derived where the
+/// orphan rules allow it in `auto` mode.
+#[cfg(test)]
+mod test_derive_auto {
+    use enum_conversion::prelude::*;
+    use std::convert::TryFrom;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveTryFrom(auto)]
+    enum Test<'a, U> {
+        F1(&'a U),
+        F2(bool),
+        F3(Vec<U>),
+    }
+
+    #[test]
+    fn test_try_from_safe_variants() {
+        let test = Test::<'static, i32>::F2(true);
+        let boolean = bool::try_from(test).expect("Test failed");
+        assert!(boolean);
+        let test = Test::<'static, i32>::F3(vec![1, 2]);
+        let vec = Vec::<i32>::try_from(test).expect("Test failed");
+        assert_eq!(vec, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_try_to_skipped_variant() {
+        let test = Test::F1(&3);
+        let int: &i32 = test.try_to().expect("Test failed");
+        assert_eq!(*int, 3);
+    }
+}
+
 /// Test that errors are configured correctly.
 #[cfg(test)]
 mod test_derive_errors {
@@ -104,6 +458,1402 @@ mod test_derive_errors {
     }
 }
 
+/// Test the metadata describing the variants of an enum.
+#[cfg(test)]
+mod test_derive_reflection {
+    use enum_conversion::prelude::*;
+
+    #[EnumConversions]
+    enum Test<'a, T> {
+        Int(i32),
+        Text(&'a str),
+        #[cfg(any())]
+        Missing(DoesNotExist),
+        List(Vec<T>),
+        #[deprecated(note = "Use Int instead.")]
+        Byte(u8),
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(Test::<bool>::VARIANT_COUNT, 4);
+        assert_eq!(
+            Test::<bool>::VARIANT_NAMES,
+            &["Int", "Text", "List", "Byte"]
+        );
+        assert_eq!(
+            Test::<bool>::VARIANT_TYPE_NAMES,
+            &["i32", "&'a str", "Vec<T>", "u8"]
+        );
+    }
+
+    #[test]
+    fn test_active_variant() {
+        let list: Test<bool> = vec![true].into();
+        assert_eq!(list.variant_index(), 2);
+        assert_eq!(list.variant_name(), "List");
+        let byte: Test<bool> = 1_u8.into();
+        assert_eq!(byte.variant_index(), 3);
+        assert_eq!(byte.variant_name(), "Byte");
+    }
+
+    #[test]
+    fn test_same_variant() {
+        let first: Test<bool> = 1.into();
+        let second: Test<bool> = 2.into();
+        let text: Test<bool> = "1".into();
+        assert!(first.same_variant(&second));
+        assert!(!first.same_variant(&text));
+    }
+}
+
+/// Test the fieldless kind enum.
+#[cfg(test)]
+mod test_derive_kind {
+    use enum_conversion::prelude::*;
+    use std::collections::HashMap;
+
+    #[EnumConversions]
+    #[DeriveKind]
+    pub enum Test<'a, T> {
+        Int(i32),
+        Text(&'a str),
+        #[cfg(any())]
+        Missing(DoesNotExist),
+        List(Vec<T>),
+        #[deprecated(note = "Use Int instead.")]
+        Byte(u8),
+    }
+
+    #[test]
+    fn test_kind() {
+        let list: Test<bool> = vec![true].into();
+        assert_eq!(list.kind(), TestKind::List);
+        let byte: Test<bool> = 1_u8.into();
+        assert_eq!(byte.kind(), TestKind::Byte);
+        let mut counts = HashMap::new();
+        for value in [list, byte, Test::Int(1), Test::Int(2)] {
+            *counts.entry(value.kind()).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&TestKind::Int], 2);
+        assert_eq!(counts[&TestKind::List], 1);
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(
+            TestKind::ALL,
+            &[
+                TestKind::Int,
+                TestKind::Text,
+                TestKind::List,
+                TestKind::Byte
+            ]
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(TestKind::Text.to_string(), "Text");
+        assert_eq!(
+            "List".parse::<TestKind>().expect("Test failed"),
+            TestKind::List
+        );
+        let error = "Missing".parse::<TestKind>().unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "ParseKindError :: <Missing> is not a variant of <TestKind>"
+        );
+    }
+
+    #[test]
+    fn test_kind_of() {
+        assert_eq!(Test::<bool>::kind_of::<i32>(), TestKind::Int);
+        assert_eq!(Test::<bool>::kind_of::<Vec<bool>>(), TestKind::List);
+        assert_eq!(Test::<bool>::kind_of::<&str>(), TestKind::Text);
+    }
+}
+
+/// Test the generated visitor traits.
+#[cfg(test)]
+mod test_derive_visitor {
+    use enum_conversion::prelude::*;
+
+    #[EnumConversions]
+    #[DeriveVisitor]
+    enum Test<'a, T> {
+        Int(i32),
+        Text(&'a str),
+        #[cfg(any())]
+        Missing(DoesNotExist),
+        ListOfT(Vec<T>),
+    }
+
+    /// Describes the value held by the enum.
+    struct Describe;
+
+    impl<'a, T> TestVisitor<'a, T> for Describe {
+        type Output = String;
+
+        fn visit_int(&mut self, value: i32) -> String {
+            format!("int {}", value)
+        }
+
+        fn visit_text(&mut self, value: &'a str) -> String {
+            format!("text {}", value)
+        }
+
+        fn visit_list_of_t(&mut self, value: Vec<T>) -> String {
+            format!("list of {}", value.len())
+        }
+    }
+
+    /// Returns a reference into the visited enum.
+    struct AsText;
+
+    impl<'v, 'a, T> TestRefVisitor<'v, 'a, T> for AsText {
+        type Output = Option<&'v str>;
+
+        fn visit_int(&mut self, _: &'v i32) -> Self::Output {
+            None
+        }
+
+        fn visit_text(&mut self, value: &'v &'a str) -> Self::Output {
+            Some(value)
+        }
+
+        fn visit_list_of_t(&mut self, _: &'v Vec<T>) -> Self::Output {
+            None
+        }
+    }
+
+    /// Counts the visits while modifying the values.
+    struct Increment(usize);
+
+    impl<'v, 'a> TestMutVisitor<'v, 'a, i32> for Increment {
+        type Output = ();
+
+        fn visit_int(&mut self, value: &'v mut i32) {
+            self.0 += 1;
+            *value += 1;
+        }
+
+        fn visit_text(&mut self, _: &'v mut &'a str) {
+            self.0 += 1;
+        }
+
+        fn visit_list_of_t(&mut self, value: &'v mut Vec<i32>) {
+            self.0 += 1;
+            value.iter_mut().for_each(|v| *v += 1);
+        }
+    }
+
+    #[test]
+    fn test_accept() {
+        let int: Test<bool> = 1.into();
+        assert_eq!(int.accept(&mut Describe), "int 1");
+        let list: Test<bool> = vec![true, false].into();
+        assert_eq!(list.accept(&mut Describe), "list of 2");
+    }
+
+    #[test]
+    fn test_accept_ref() {
+        let text: Test<bool> = "hello".into();
+        assert_eq!(text.accept_ref(&mut AsText), Some("hello"));
+        let int: Test<bool> = 1.into();
+        assert_eq!(int.accept_ref(&mut AsText), None);
+    }
+
+    #[test]
+    fn test_accept_mut() {
+        let mut visitor = Increment(0);
+        let mut int: Test<i32> = 1.into();
+        int.accept_mut(&mut visitor);
+        let mut list: Test<i32> = vec![1, 2].into();
+        list.accept_mut(&mut visitor);
+        assert_eq!(visitor.0, 2);
+        assert_eq!(int.try_to().ok(), Some(2));
+        let list: Vec<i32> = list.try_to().expect("Test failed");
+        assert_eq!(list, vec![2, 3]);
+    }
+}
+
+/// Test branching on the types held by an enum.
+#[cfg(test)]
+mod test_match_variant {
+    use enum_conversion::prelude::*;
+
+    #[EnumConversions]
+    enum Test<T> {
+        Int(i32),
+        Text(String),
+        List(Vec<T>),
+    }
+
+    fn describe<T>(value: Test<T>) -> String {
+        match_variant!(value,
+            i32 => |int| format!("int {}", int),
+            Vec<T> => |list| format!("list of {}", list.len()),
+            _ => "other".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_owned() {
+        assert_eq!(describe::<bool>(1.into()), "int 1");
+        assert_eq!(describe::<bool>(vec![true].into()), "list of 1");
+        assert_eq!(describe::<bool>("a".to_string().into()), "other");
+    }
+
+    #[test]
+    fn test_ref() {
+        let value: Test<bool> = "hello".to_string().into();
+        let text = match_variant!(&value,
+            String => |text| text.as_str(),
+            i32 => |_| "int",
+            _ => "list",
+        );
+        assert_eq!(text, "hello");
+    }
+
+    #[test]
+    fn test_mut() {
+        let mut value: Test<bool> = 1.into();
+        match_variant!(&mut value,
+            String => |text| text.push('!'),
+            i32 => |int| *int += 1,
+            _ => (),
+        );
+        assert_eq!(value.try_to().ok(), Some(2));
+    }
+
+    /// Only the default arm is required.
+    #[test]
+    fn test_default_only() {
+        let value: Test<bool> = 1.into();
+        assert!(match_variant!(value, _ => true));
+    }
+}
+
+/// Test the conversions through the pointers of
+/// transparent variants.
+#[cfg(test)]
+mod test_derive_transparent {
+    use enum_conversion::prelude::*;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq)]
+    struct Frame {
+        data: [u8; 16],
+    }
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Test<T> {
+        Small(u8),
+        #[Transparent]
+        Big(Box<Frame>),
+        #[Transparent]
+        Shared(Rc<String>),
+        #[Transparent]
+        List(std::sync::Arc<Vec<T>>),
+        #[Transparent]
+        Name(Arc<str>),
+    }
+
+    #[test]
+    fn test_box() {
+        let mut test: Test<bool> = Frame { data: [1; 16] }.into();
+        assert_eq!(test, Test::Big(Box::new(Frame { data: [1; 16] })));
+        let frame: &mut Frame = (&mut test).try_to().expect("Test failed");
+        frame.data[0] = 2;
+        let frame: &Frame = (&test).try_to().expect("Test failed");
+        assert_eq!(frame.data[0], 2);
+        let frame: Frame = test.try_to().expect("Test failed");
+        assert_eq!(frame.data[1], 1);
+        let boxed: Box<Frame> = Test::<bool>::from(frame).try_to().expect("Test failed");
+        assert_eq!(boxed.data[0], 2);
+    }
+
+    #[test]
+    fn test_shared() {
+        let test: Test<bool> = "hello".to_string().into();
+        let text: &String = (&test).try_to().expect("Test failed");
+        assert_eq!(text, "hello");
+        let rc: Rc<String> = test.try_to().expect("Test failed");
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let test: Test<bool> = vec![true].into();
+        let list: &Vec<bool> = (&test).try_to().expect("Test failed");
+        assert_eq!(list, &vec![true]);
+        let arc: Arc<Vec<bool>> = test.try_to().expect("Test failed");
+        assert_eq!(*arc, vec![true]);
+
+        let test: Test<bool> = Arc::<str>::from("name").into();
+        let name: &str = (&test).try_to().expect("Test failed");
+        assert_eq!(name, "name");
+    }
+
+    #[test]
+    fn test_wrong_variant() {
+        let test: Test<bool> = 1_u8.into();
+        let text: Result<&String, _> = (&test).try_to();
+        let error = text.unwrap_err().to_string();
+        let expected =
+            "EnumConversionError :: Active field of enum <Test<T>> is not of type <String>";
+        assert_eq!(error, expected);
+        let frame: Result<Frame, _> = test.try_to();
+        let error = frame.unwrap_err().to_string();
+        let expected =
+            "EnumConversionError :: Active field of enum <Test<T>> is not of type <Frame>";
+        assert_eq!(error, expected);
+    }
+}
+
+/// Test conversions of enums behind smart pointers.
+#[cfg(test)]
+mod test_smart_pointers {
+    use enum_conversion::prelude::*;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq)]
+    struct Frame {
+        data: Vec<u8>,
+    }
+
+    #[derive(Debug)]
+    #[EnumConversions]
+    enum Event {
+        Tick(u64),
+        Frame(Frame),
+    }
+
+    #[test]
+    fn test_box() {
+        let event: Box<Event> = Box::new(Frame { data: vec![1, 2] }.into());
+        let frame: Frame = event.try_to().expect("Test failed");
+        assert_eq!(frame.data, vec![1, 2]);
+        let event: Box<Event> = Box::new(1.into());
+        let frame: Result<Frame, _> = event.try_to();
+        assert!(frame.is_err());
+    }
+
+    #[test]
+    fn test_arc_variant() {
+        let event: Arc<Event> = Arc::new(Frame { data: vec![1, 2] }.into());
+        let frame: ArcVariant<Event, Frame> = event.clone().try_to().expect("Test failed");
+        assert_eq!(Arc::strong_count(&event), 2);
+        let handle = std::thread::spawn(move || frame.data.len());
+        assert_eq!(handle.join().expect("Test failed"), 2);
+        assert_eq!(Arc::strong_count(&event), 1);
+
+        let tick = ArcVariant::<Event, u64>::try_new(event).unwrap_err();
+        assert!(matches!(*tick, Event::Frame(_)));
+    }
+
+    #[test]
+    fn test_rc_variant() {
+        let event: Rc<Event> = Rc::new(3_u64.into());
+        let tick = RcVariant::<Event, u64>::try_new(event).expect("Test failed");
+        let copy = tick.clone();
+        assert_eq!(*copy, 3);
+        assert_eq!(Rc::strong_count(RcVariant::owner(&tick)), 2);
+        drop(copy);
+        let event = RcVariant::into_owner(tick);
+        assert_eq!(Rc::strong_count(&event), 1);
+    }
+}
+
+/// Test borrowing variants out of a `RefCell`.
+#[cfg(test)]
+mod test_ref_cell {
+    use enum_conversion::prelude::*;
+    use std::cell::{Ref, RefCell, RefMut};
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Test {
+        Int(i32),
+        Text(String),
+    }
+
+    #[test]
+    fn test_ref_variant() {
+        let cell = RefCell::new(Test::from("hello".to_string()));
+        let text: Ref<String> = ref_variant(cell.borrow()).expect("Test failed");
+        assert_eq!(*text, "hello");
+        let other = cell.borrow();
+        assert!(ref_variant::<_, i32>(other).is_err());
+    }
+
+    #[test]
+    fn test_ref_mut_variant() {
+        let cell = RefCell::new(Test::from(1));
+        {
+            let mut int: RefMut<i32> = ref_mut_variant(cell.borrow_mut()).expect("Test failed");
+            *int += 1;
+        }
+        assert_eq!(*cell.borrow(), Test::Int(2));
+        let borrow = ref_mut_variant::<_, String>(cell.borrow_mut()).unwrap_err();
+        assert_eq!(*borrow, Test::Int(2));
+        assert!(cell.try_borrow().is_err());
+    }
+}
+
+/// Test the iterator adapters selecting variants by type.
+#[cfg(test)]
+mod test_variant_iterator {
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Test<T> {
+        Int(i32),
+        Text(String),
+        List(Vec<T>),
+    }
+
+    fn values() -> Vec<Test<bool>> {
+        vec![
+            Test::Int(1),
+            Test::Text("one".to_string()),
+            Test::Int(2),
+            Test::List(vec![true]),
+            Test::Text("two".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_variants_of() {
+        let texts: Vec<String> = values().into_iter().variants_of::<String>().collect();
+        assert_eq!(texts, vec!["one".to_string(), "two".to_string()]);
+        let last = values().into_iter().variants_of::<i32>().next_back();
+        assert_eq!(last, Some(2));
+    }
+
+    #[test]
+    fn test_refs_of() {
+        let values = values();
+        let ints: Vec<&i32> = values.iter().refs_of::<i32>().collect();
+        assert_eq!(ints, vec![&1, &2]);
+        assert_eq!(values.iter().refs_of::<Vec<bool>>().count(), 1);
+    }
+
+    #[test]
+    fn test_muts_of() {
+        let mut values = values();
+        for int in values.iter_mut().muts_of::<i32>() {
+            *int *= 10;
+        }
+        assert_eq!(values[0], Test::Int(10));
+        assert_eq!(values[2], Test::Int(20));
+    }
+
+    #[test]
+    fn test_count_of() {
+        let mut values = values();
+        assert_eq!(values.iter().count_of::<String>(), 2);
+        assert_eq!(values.iter_mut().count_of::<i32>(), 2);
+        assert_eq!(values.into_iter().count_of::<Vec<bool>>(), 1);
+    }
+
+    /// The adapters only pull as many items as needed.
+    #[test]
+    fn test_lazy() {
+        let mut pulled = 0;
+        let first = values()
+            .into_iter()
+            .inspect(|_| pulled += 1)
+            .variants_of::<String>()
+            .next();
+        assert_eq!(first, Some("one".to_string()));
+        assert_eq!(pulled, 2);
+    }
+}
+
+/// Test converting whole collections of enums.
+#[cfg(test)]
+mod test_collections {
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Test {
+        Unsigned(u64),
+        Signed(i64),
+    }
+
+    #[test]
+    fn test_vec_try_to() {
+        let values: Vec<Test> = vec![1u64.into(), 2u64.into(), 3u64.into()];
+        let unsigned: Vec<u64> = values.try_to().expect("Test failed");
+        assert_eq!(unsigned, vec![1, 2, 3]);
+        let empty: Vec<Test> = vec![];
+        let signed: Vec<i64> = empty.try_to().expect("Test failed");
+        assert!(signed.is_empty());
+    }
+
+    /// On failure, the position of the first mismatch is reported
+    /// and the elements are given back unchanged.
+    #[test]
+    fn test_vec_try_to_fails() {
+        let values: Vec<Test> = vec![1u64.into(), (-2i64).into(), (-3i64).into()];
+        let error = TryTo::<Vec<u64>>::try_to(values).unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(
+            error.values,
+            vec![Test::Unsigned(1), Test::Signed(-2), Test::Signed(-3)]
+        );
+        assert_eq!(
+            error.to_string(),
+            "VecConversionError :: Element 1 of type \
+             <tests::test_collections::Test> holds a different variant"
+        );
+    }
+
+    /// The empty variant fits in the niche of the `Box`, so
+    /// the enum has the same layout as the values.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Boxed {
+        Value(Box<u64>),
+        Empty(()),
+    }
+
+    #[test]
+    fn test_vec_reuses_allocation() {
+        use std::mem::{align_of, size_of};
+        assert_eq!(size_of::<Boxed>(), size_of::<Box<u64>>());
+        assert_eq!(align_of::<Boxed>(), align_of::<Box<u64>>());
+        let mut values: Vec<Boxed> = Vec::with_capacity(4);
+        values.extend([Box::new(1).into(), Box::new(2).into()]);
+        let pointer = values.as_ptr() as usize;
+        let boxes: Vec<Box<u64>> = values.try_to().expect("Test failed");
+        assert_eq!(boxes.as_ptr() as usize, pointer);
+        assert_eq!(boxes.capacity(), 4);
+        assert_eq!(boxes, vec![Box::new(1), Box::new(2)]);
+    }
+
+    #[test]
+    fn test_all_refs() {
+        let values: Vec<Test> = vec![(-1i64).into(), (-2i64).into()];
+        let signed: Option<Vec<&i64>> = all_refs(&values);
+        assert_eq!(signed, Some(vec![&-1, &-2]));
+        assert_eq!(all_refs::<_, u64>(&values), None);
+        assert_eq!(all_refs::<Test, u64>(&[]), Some(vec![]));
+    }
+}
+
+/// Test the buckets storing the values of each variant separately.
+#[cfg(test)]
+mod test_derive_buckets {
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[EnumConversions]
+    #[DeriveBuckets]
+    enum Event<T> {
+        Click(i32),
+        Key(String),
+        Custom(Vec<T>),
+    }
+
+    /// A type that is neither `Debug` nor `Clone`.
+    pub struct Handle(u32);
+
+    #[EnumConversions]
+    #[DeriveBuckets]
+    enum Handles {
+        Int(i32),
+        Handle(Handle),
+    }
+
+    fn events() -> Vec<Event<bool>> {
+        vec![
+            Event::Key("a".to_string()),
+            Event::Click(1),
+            Event::Custom(vec![true]),
+            Event::Click(2),
+            Event::Key("b".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_buckets() {
+        let buckets: EventBuckets<bool> = events().into_iter().collect();
+        assert_eq!(buckets.len(), 5);
+        assert_eq!(buckets.bucket::<i32>(), &[1, 2]);
+        assert_eq!(
+            buckets.bucket::<String>(),
+            &["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(buckets.bucket::<Vec<bool>>(), &[vec![true]]);
+    }
+
+    #[test]
+    fn test_into_iter_ordered() {
+        let mut buckets = EventBuckets::new();
+        assert!(buckets.is_empty());
+        buckets.extend(events());
+        for click in buckets.bucket_mut::<i32>() {
+            *click *= 10;
+        }
+        buckets.push(Event::Click(3));
+        let ordered: Vec<Event<bool>> = buckets.into_iter_ordered().collect();
+        assert_eq!(
+            ordered,
+            vec![
+                Event::Key("a".to_string()),
+                Event::Click(10),
+                Event::Custom(vec![true]),
+                Event::Click(20),
+                Event::Key("b".to_string()),
+                Event::Click(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debug_and_clone() {
+        let buckets: EventBuckets<bool> = events().into_iter().take(2).collect();
+        assert_eq!(
+            format!("{:?}", buckets.clone()),
+            "EventBuckets { order: [1, 0], Click: [1], Key: [\"a\"], Custom: [] }"
+        );
+    }
+
+    /// Buckets can hold types that are neither `Debug` nor `Clone`.
+    #[test]
+    fn test_not_debug() {
+        let buckets: HandlesBuckets = vec![Handle(1).into(), 2.into()].into_iter().collect();
+        let handles = buckets.bucket::<Handle>();
+        assert_eq!(handles.len(), 1);
+        assert_eq!(handles[0].0, 1);
+    }
+}
+
+/// Test the map storing at most one value per variant.
+#[cfg(test)]
+mod test_variant_map {
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[EnumConversions]
+    enum Extension<'a> {
+        Name(&'a str),
+        Retries(u32),
+        Tags(Vec<String>),
+    }
+
+    /// The removed variant in between gets no slot.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Sparse {
+        First(u8),
+        #[cfg(any())]
+        Missing(DoesNotExist),
+        Last(u16),
+    }
+
+    #[test]
+    fn test_cfg_variant_slots() {
+        assert_eq!(<enum___conversion___Sparse::Last as MarkerIndex>::INDEX, 1);
+        let mut map: VariantMap<Sparse> = VariantMap::new();
+        assert_eq!(map.insert(2u16), None);
+        assert_eq!(map.insert(1u8), None);
+        let values: Vec<&Sparse> = map.iter().collect();
+        assert_eq!(values, vec![&Sparse::First(1), &Sparse::Last(2)]);
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        // The values borrow from the stack, so are not `'static`.
+        let name = String::from("conn-1");
+        let mut map: VariantMap<Extension> = VariantMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(name.as_str()), None);
+        assert_eq!(map.insert(3u32), None);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get::<&str>(), Some(&"conn-1"));
+        assert_eq!(map.get::<u32>(), Some(&3));
+        assert_eq!(map.get::<Vec<String>>(), None);
+        assert!(!map.contains::<Vec<String>>());
+        assert_eq!(map.insert(4u32), Some(Extension::Retries(3)));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_get_mut_and_remove() {
+        let mut map: VariantMap<Extension> = VariantMap::new();
+        map.insert(vec!["a".to_string()]);
+        map.get_mut::<Vec<String>>()
+            .expect("Test failed")
+            .push("b".to_string());
+        assert_eq!(
+            map.remove::<Vec<String>>(),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(map.remove::<Vec<String>>(), None);
+        assert!(map.is_empty());
+    }
+
+    /// Values are iterated over in the order their variants are declared.
+    #[test]
+    fn test_iter() {
+        let mut map: VariantMap<Extension> = vec![Extension::Retries(1), Extension::Name("a")]
+            .into_iter()
+            .collect();
+        for value in map.iter_mut() {
+            if let Extension::Retries(retries) = value {
+                *retries += 1;
+            }
+        }
+        let values: Vec<&Extension> = map.iter().collect();
+        assert_eq!(values, vec![&Extension::Name("a"), &Extension::Retries(2)]);
+        assert_eq!(format!("{:?}", map), "{Name(\"a\"), Retries(2)}");
+        let values: Vec<Extension> = map.clone().into_iter().collect();
+        assert_eq!(values, vec![Extension::Name("a"), Extension::Retries(2)]);
+        map.clear();
+        assert!(map.is_empty());
+    }
+}
+
+/// Test the sets of variants stored as bits.
+#[cfg(test)]
+mod test_derive_variant_set {
+    use enum_conversion::prelude::*;
+
+    #[EnumConversions]
+    #[DeriveVariantSet]
+    enum Message<T> {
+        Text(String),
+        Number(i64),
+        Batch(Vec<T>),
+    }
+
+    #[EnumConversions]
+    #[DeriveVariantSet]
+    enum Event<'a> {
+        Click(u32),
+        Text(&'a str),
+    }
+
+    /// The removed variant in between gets no bit.
+    #[EnumConversions]
+    #[DeriveVariantSet]
+    enum Sparse {
+        First(u8),
+        #[cfg(any())]
+        Missing(DoesNotExist),
+        Last(u16),
+    }
+
+    /// Sets can be built in constants.
+    const TEXTUAL: MessageSet<u8> = MessageSet::new().with::<String>().with::<Vec<u8>>();
+    const NUMERIC: MessageSet<u8> = MessageSet::from_kinds(&[MessageKind::Number]);
+
+    #[test]
+    fn test_contains() {
+        let text: Message<u8> = "hi".to_string().into();
+        let number: Message<u8> = 1i64.into();
+        assert!(TEXTUAL.contains(&text));
+        assert!(!TEXTUAL.contains(&number));
+        assert!(NUMERIC.contains(&number));
+        assert!(TEXTUAL.contains_type::<Vec<u8>>());
+        assert!(TEXTUAL.contains_kind(MessageKind::Batch));
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set = MessageSet::<u8>::new();
+        assert!(set.is_empty());
+        assert!(set.insert::<i64>());
+        assert!(!set.insert::<i64>());
+        assert!(set.insert_kind(MessageKind::Text));
+        assert_eq!(set.len(), 2);
+        assert!(set.remove::<i64>());
+        assert!(!set.remove::<i64>());
+        assert!(!set.remove_kind(MessageKind::Batch));
+        assert_eq!(set, MessageSet::from_kinds(&[MessageKind::Text]));
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let all = MessageSet::<u8>::all();
+        assert_eq!(all.len(), 3);
+        assert_eq!(TEXTUAL | NUMERIC, all);
+        assert!((TEXTUAL & NUMERIC).is_empty());
+        assert_eq!(all - NUMERIC, TEXTUAL);
+        assert_eq!(!TEXTUAL, NUMERIC);
+        assert_eq!(TEXTUAL ^ all, NUMERIC);
+        assert!(TEXTUAL.is_subset(&all));
+        assert!(all.is_superset(&NUMERIC));
+        assert!(TEXTUAL.is_disjoint(&NUMERIC));
+    }
+
+    #[test]
+    fn test_iter() {
+        let kinds: Vec<MessageKind> = TEXTUAL.iter().collect();
+        assert_eq!(kinds, vec![MessageKind::Text, MessageKind::Batch]);
+        let set: MessageSet<u8> = kinds.into_iter().collect();
+        assert_eq!(set, TEXTUAL);
+        assert_eq!(format!("{:?}", NUMERIC), "{Number}");
+    }
+    #[test]
+    fn test_cfg_variant_bits() {
+        let set = SparseSet::new().with::<u16>();
+        assert!(set.contains(&Sparse::Last(1)));
+        assert!(!set.contains(&Sparse::First(1)));
+        assert_eq!(
+            set.complement(),
+            SparseSet::from_kinds(&[SparseKind::First])
+        );
+        let kinds: Vec<SparseKind> = SparseSet::all().iter().collect();
+        assert_eq!(kinds, vec![SparseKind::First, SparseKind::Last]);
+    }
+
+    /// The iterator does not borrow the lifetimes of the enum.
+    #[test]
+    fn test_iter_with_lifetime() {
+        let text = String::from("hi");
+        let event = Event::from(text.as_str());
+        let set = EventSet::new().with::<&str>();
+        assert!(set.contains(&event));
+        let kinds: Vec<EventKind> = set.iter().collect();
+        assert_eq!(kinds, vec![EventKind::Text]);
+        assert_eq!(format!("{:?}", set), "{Text}");
+    }
+}
+
+/// Test splitting a channel of enums into channels per type.
+#[cfg(test)]
+mod test_demux {
+    use std::sync::mpsc::channel;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Event {
+        Click(u32),
+        Key(char),
+        Text(String),
+    }
+
+    #[test]
+    fn test_routing() {
+        let (sender, receiver) = channel();
+        let mut demux = Demux::new(receiver, Unclaimed::Drop);
+        let clicks = demux.subscribe::<u32>();
+        let keys = demux.subscribe::<char>();
+        let handle = thread::spawn(move || demux.run());
+        sender.send(Event::Click(1)).expect("Test failed");
+        sender
+            .send(Event::Text("dropped".to_string()))
+            .expect("Test failed");
+        sender.send(Event::Key('a')).expect("Test failed");
+        sender.send(Event::Click(2)).expect("Test failed");
+        drop(sender);
+        handle.join().expect("Test failed").expect("Test failed");
+        assert_eq!(clicks.iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(keys.iter().collect::<Vec<_>>(), vec!['a']);
+    }
+
+    /// Values of dropped receivers are unclaimed too.
+    #[test]
+    fn test_handler() {
+        let unclaimed = Arc::new(Mutex::new(vec![]));
+        let (sender, receiver) = channel();
+        let mut demux = Demux::new(receiver, {
+            let unclaimed = unclaimed.clone();
+            Unclaimed::Handle(Box::new(move |event| {
+                unclaimed.lock().expect("Test failed").push(event)
+            }))
+        });
+        let clicks = demux.subscribe::<u32>();
+        drop(demux.subscribe::<char>());
+        demux.route(Event::Click(1)).expect("Test failed");
+        demux.route(Event::Key('a')).expect("Test failed");
+        demux
+            .route(Event::Text("b".to_string()))
+            .expect("Test failed");
+        drop(sender);
+        demux.run().expect("Test failed");
+        assert_eq!(clicks.recv(), Ok(1));
+        assert_eq!(
+            *unclaimed.lock().expect("Test failed"),
+            vec![Event::Key('a'), Event::Text("b".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let (sender, receiver) = channel();
+        let mut demux = Demux::new(receiver, Unclaimed::Error);
+        let clicks = demux.subscribe::<u32>();
+        sender.send(Event::Click(1)).expect("Test failed");
+        sender.send(Event::Key('a')).expect("Test failed");
+        sender.send(Event::Click(2)).expect("Test failed");
+        let error = demux.run().expect_err("Test failed");
+        assert_eq!(error.variant, "Key");
+        assert_eq!(error.value, Event::Key('a'));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "DemuxError :: No receiver for variant <Key> of <{}>",
+                std::any::type_name::<Event>()
+            )
+        );
+        // Routing stopped, so the receiver is disconnected.
+        assert_eq!(clicks.iter().collect::<Vec<_>>(), vec![1]);
+    }
+}
+
+/// Test calling the handlers registered for variant types.
+#[cfg(test)]
+mod test_dispatcher {
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Event<'a> {
+        Click(u32),
+        Key(char),
+        Text(&'a str),
+    }
+
+    /// The removed variant in between gets no handler slot.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Sparse {
+        First(u8),
+        #[cfg(any())]
+        Missing(DoesNotExist),
+        Last(u16),
+    }
+
+    #[test]
+    fn test_cfg_variant_handlers() {
+        let mut last = 0;
+        {
+            let mut dispatcher = Dispatcher::new().on(|value: u16| last = value);
+            assert_eq!(dispatcher.unhandled(), vec!["First"]);
+            dispatcher.dispatch(Sparse::Last(2)).expect("Test failed");
+            assert_eq!(dispatcher.dispatch(Sparse::First(1)), Err(Sparse::First(1)));
+        }
+        assert_eq!(last, 2);
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut clicks = 0;
+        let mut keys = String::new();
+        {
+            let mut dispatcher = Dispatcher::new()
+                .on(|click: u32| clicks += click)
+                .on_ref(|key: &char| keys.push(*key));
+            assert!(dispatcher.handles::<u32>());
+            assert!(!dispatcher.handles::<&str>());
+            dispatcher.dispatch(Event::Click(2)).expect("Test failed");
+            dispatcher.dispatch(Event::Key('a')).expect("Test failed");
+            dispatcher.dispatch(Event::Click(3)).expect("Test failed");
+            assert_eq!(dispatcher.dispatch(Event::Text("b")), Err(Event::Text("b")));
+        }
+        assert_eq!(clicks, 5);
+        assert_eq!(keys, "a");
+    }
+
+    /// Registering a handler again replaces the previous one.
+    #[test]
+    fn test_replace() {
+        let mut seen = vec![];
+        let mut dispatcher = Dispatcher::new()
+            .on(|_: u32| panic!("Test failed"))
+            .on(|click: u32| seen.push(click));
+        dispatcher.dispatch(Event::Click(1)).expect("Test failed");
+        drop(dispatcher);
+        assert_eq!(seen, vec![1]);
+    }
+
+    #[test]
+    fn test_unhandled() {
+        let dispatcher = Dispatcher::<Event>::new().on(|_: char| ());
+        assert_eq!(dispatcher.unhandled(), vec!["Click", "Text"]);
+        assert_eq!(
+            format!("{:?}", dispatcher),
+            "Dispatcher { unhandled: [\"Click\", \"Text\"], .. }"
+        );
+        let dispatcher = dispatcher.on(|_: u32| ()).on(|_: &str| ());
+        assert!(dispatcher.unhandled().is_empty());
+        dispatcher.assert_exhaustive();
+    }
+
+    #[test]
+    #[should_panic(expected = "has no handler for variants: Click, Text")]
+    fn test_assert_exhaustive() {
+        Dispatcher::<Event>::new()
+            .on(|_: char| ())
+            .assert_exhaustive();
+    }
+}
+
+/// Test replacing the active variant in place.
+#[cfg(test)]
+mod test_transitions {
+    use enum_conversion::prelude::*;
+
+    /// None of the states implement `Default` or `Clone`.
+    #[derive(Debug, PartialEq)]
+    struct Connecting {
+        attempts: u32,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Open {
+        session: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Closed;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[Transitions(Connecting => Open, Open => Closed, Connecting => Closed)]
+    enum Connection {
+        Connecting(Connecting),
+        Open(Open),
+        Closed(Closed),
+    }
+
+    /// Without `#[Transitions]`, any transition is allowed.
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Value<'a, T> {
+        Borrowed(&'a T),
+        Owned(Vec<T>),
+    }
+
+    #[test]
+    fn test_transition() {
+        let mut connection = Connection::Connecting(Connecting { attempts: 2 });
+        connection
+            .transition::<Connecting, Open>(|state| Open {
+                session: format!("after {} attempts", state.attempts),
+            })
+            .expect("Test failed");
+        assert_eq!(
+            connection,
+            Connection::Open(Open {
+                session: "after 2 attempts".to_string()
+            })
+        );
+        connection
+            .transition::<Open, Closed>(|_| Closed)
+            .expect("Test failed");
+        assert_eq!(connection, Connection::Closed(Closed));
+    }
+
+    /// The enum is left untouched if it holds another variant.
+    #[test]
+    fn test_wrong_variant() {
+        let mut connection = Connection::Open(Open {
+            session: "a".to_string(),
+        });
+        let error = connection
+            .transition::<Connecting, Closed>(|_| panic!("Test failed"))
+            .expect_err("Test failed");
+        assert_eq!(
+            error.to_string(),
+            "EnumConversionError :: Active field of enum <Connection> is not of type <Connecting>"
+        );
+        assert_eq!(
+            connection,
+            Connection::Open(Open {
+                session: "a".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_all_allowed() {
+        let value = 1;
+        let mut converted: Value<i32> = Value::Borrowed(&value);
+        converted
+            .transition::<&i32, Vec<i32>>(|value| vec![*value; 2])
+            .expect("Test failed");
+        assert_eq!(converted, Value::Owned(vec![1, 1]));
+        converted
+            .transition::<Vec<i32>, &i32>(|_| &value)
+            .expect("Test failed");
+        assert_eq!(converted, Value::Borrowed(&1));
+    }
+}
+
+/// Test the adapters of futures resolving to an enum.
+#[cfg(test)]
+mod test_variant_future {
+    use std::future::Future;
+    use std::pin::{pin, Pin};
+    use std::task::{Context, Poll};
+
+    use enum_conversion::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    enum Event {
+        Click(u32),
+        Key(char),
+    }
+
+    /// Polls the future until it is ready.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = super::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// Is pending once before resolving.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    async fn recv(event: Event) -> Event {
+        YieldOnce(false).await;
+        event
+    }
+
+    #[test]
+    fn test_variant() {
+        assert_eq!(block_on(recv(Event::Click(1)).variant::<u32>()), Ok(1));
+        assert_eq!(
+            block_on(recv(Event::Key('a')).variant::<u32>()),
+            Err(Event::Key('a'))
+        );
+    }
+
+    /// The adapter is pending while the future is.
+    #[test]
+    fn test_pending() {
+        let mut future = pin!(recv(Event::Key('a')).variant::<char>());
+        let waker = super::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(Ok('a')));
+    }
+
+    #[test]
+    fn test_expect_variant() {
+        let key = block_on(async { recv(Event::Key('a')).expect_variant::<char>().await });
+        assert_eq!(key, 'a');
+    }
+
+    #[test]
+    #[should_panic(expected = "Future resolved to another variant")]
+    fn test_expect_variant_panics() {
+        block_on(recv(Event::Key('a')).expect_variant::<u32>());
+    }
+}
+
+/// A waker that does nothing, for polling futures by hand.
+#[cfg(test)]
+fn noop_waker() -> std::task::Waker {
+    struct Noop;
+
+    impl std::task::Wake for Noop {
+        fn wake(self: std::sync::Arc<Self>) {}
+    }
+
+    std::sync::Arc::new(Noop).into()
+}
+
+/// Test the projections from pinned enums.
+#[cfg(test)]
+mod test_derive_pin {
+    use enum_conversion::prelude::*;
+    use std::future::Future;
+    use std::marker::PhantomPinned;
+    use std::pin::{pin, Pin};
+    use std::task::{Context, Poll};
+
+    /// A future that is ready after being polled a number of times.
+    /// It must not be moved once polled.
+    struct Countdown {
+        remaining: u32,
+        _pinned: PhantomPinned,
+    }
+
+    impl Future for Countdown {
+        type Output = &'static str;
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context) -> Poll<Self::Output> {
+            // SAFETY: The field is not pinned.
+            let remaining = unsafe { &mut self.get_unchecked_mut().remaining };
+            if *remaining == 0 {
+                Poll::Ready("done")
+            } else {
+                *remaining -= 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    #[EnumConversions]
+    #[DerivePin]
+    enum State<T> {
+        Waiting(Countdown),
+        Finished(Option<T>),
+    }
+
+    #[EnumConversions]
+    #[DerivePin]
+    enum Plain {
+        Int(i32),
+        Text(String),
+    }
+
+    fn assert_unpin<T: Unpin>() {}
+
+    #[test]
+    fn test_poll_variant() {
+        let mut state = pin!(State::<String>::from(Countdown {
+            remaining: 1,
+            _pinned: PhantomPinned,
+        }));
+        let waker = super::noop_waker();
+        let mut context = Context::from_waker(&waker);
+        let mut polls = 0;
+        loop {
+            let future: Pin<&mut Countdown> = state.as_mut().try_to().expect("Test failed");
+            polls += 1;
+            if let Poll::Ready(output) = future.poll(&mut context) {
+                state.set(State::Finished(Some(output.to_string())));
+                break;
+            }
+        }
+        assert_eq!(polls, 2);
+        let finished: Pin<&Option<String>> = state.as_ref().try_to().expect("Test failed");
+        assert_eq!(finished.as_deref(), Some("done"));
+        let waiting: Result<Pin<&mut Countdown>, _> = state.as_mut().try_to();
+        assert!(waiting.is_err());
+    }
+
+    #[test]
+    fn test_unpin() {
+        assert_unpin::<Plain>();
+        let mut plain = Plain::from(1);
+        let int: Pin<&mut i32> = Pin::new(&mut plain).try_to().expect("Test failed");
+        *int.get_mut() += 1;
+        assert_eq!(plain.try_to().ok(), Some(2));
+    }
+}
+
+/// Test the borrowed views of an enum.
+#[cfg(test)]
+mod test_derive_views {
+    use enum_conversion::prelude::*;
+
+    /// A type that cannot be cloned.
+    #[derive(Debug, PartialEq)]
+    pub struct Handle(u32);
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveViews]
+    pub enum Test<T> {
+        Int(i32),
+        Text(String),
+        List(Vec<T>),
+        #[cfg(test)]
+        Maybe(Option<T>),
+    }
+
+    #[derive(Debug, PartialEq)]
+    #[EnumConversions]
+    #[DeriveViews]
+    pub enum Handles {
+        Int(i32),
+        Handle(Handle),
+    }
+
+    fn length<T>(view: TestRef<T>) -> usize {
+        match view {
+            TestRef::Text(text) => text.len(),
+            TestRef::List(list) => list.len(),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn test_as_ref_enum() {
+        let test: Test<bool> = "hello".to_string().into();
+        let view = test.as_ref_enum();
+        let copy = view;
+        assert_eq!(length(view), 5);
+        let text: &String = copy.try_to().expect("Test failed");
+        assert_eq!(text, "hello");
+        let int: Result<&i32, _> = copy.try_to();
+        assert!(int.is_err());
+    }
+
+    #[test]
+    fn test_as_mut_enum() {
+        let mut test: Test<bool> = vec![true].into();
+        let list: &mut Vec<bool> = test.as_mut_enum().try_to().expect("Test failed");
+        list.push(false);
+        assert_eq!(test, Test::List(vec![true, false]));
+    }
+
+    #[test]
+    fn test_from_reference() {
+        let int = 3;
+        let view: TestRef<bool> = (&int).into();
+        assert_eq!(view.to_owned_enum(), Test::Int(3));
+    }
+
+    #[test]
+    fn test_to_owned_enum() {
+        let mut test: Test<bool> = vec![true].into();
+        let owned = test.as_ref_enum().to_owned_enum();
+        assert_eq!(owned, test);
+        let owned = test.as_mut_enum().to_owned_enum();
+        assert_eq!(owned, test);
+        let test: Test<bool> = Some(true).into();
+        assert_eq!(test.as_ref_enum().to_owned_enum(), test);
+    }
+
+    /// Views of variants that cannot be cloned still work, but
+    /// can only be converted back to an enum if every variant
+    /// can be cloned.
+    #[test]
+    fn test_not_clone() {
+        let test: Handles = Handle(1).into();
+        let handle: &Handle = test.as_ref_enum().try_to().expect("Test failed");
+        assert_eq!(handle, &Handle(1));
+    }
+}
+
 /// Tests that the derive macro correctly panics (thereby failing compilation) for the correct
 /// cases.
 #[cfg(test)]
@@ -113,5 +1863,9 @@ mod test_compile_failures {
         let t = trybuild::TestCases::new();
         t.compile_fail("tests/uncompilable_examples/generics_collision.rs");
         t.compile_fail("tests/uncompilable_examples/foreign_types.rs");
+        t.compile_fail("tests/uncompilable_examples/match_variant_missing_type.rs");
+        t.compile_fail("tests/uncompilable_examples/pin_with_drop.rs");
+        t.compile_fail("tests/uncompilable_examples/undeclared_transition.rs");
+        t.compile_fail("tests/uncompilable_examples/deprecated_marker.rs");
     }
 }
@@ -0,0 +1,20 @@
+//! Conversions through the marker of a deprecated
+//! variant are reported by the `deprecated` lint.
+#![deny(deprecated)]
+
+use enum_conversion::prelude::*;
+
+#[EnumConversions]
+enum Enum {
+    Int(i32),
+    #[deprecated(note = "Use Int instead.")]
+    Byte(u8),
+}
+
+fn byte<E: GetVariant<u8, enum___conversion___Enum::Byte>>(value: E) -> Option<u8> {
+    value.get_variant().ok()
+}
+
+fn main() {
+    let _ = byte(Enum::Int(1));
+}
@@ -4,7 +4,9 @@
 /// This is due to error [E0210](https://doc.rust-lang.org/error_codes/E0210.html)
 /// regarding orphan rules for trait implementations.
 ///
-/// The solution is to use the `TryTo` trait provided by this crate.
+/// The macro detects this and explains that the solution is
+/// to use the `TryTo` trait provided by this crate, or to let
+/// `#[DeriveTryFrom(auto)]` skip such variants.
 
 use enum_conversion::prelude::*;
 
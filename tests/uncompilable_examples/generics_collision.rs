@@ -4,8 +4,8 @@
 /// However, if the two generic parameters are set
 /// to be equal, the enum will contain variants with
 /// identical types. It will the be impossible to implement
-/// the `TryTo` trait and the rust compiler will complain
-/// accordingly about conflicting implementations.
+/// the `TryTo` trait, so the macro reports which variants
+/// collide and for which choice of generic parameters.
 use enum_conversion::prelude::*;
 
 struct Local<T>(T);
@@ -0,0 +1,20 @@
+/// Every type listed in `match_variant!` must be held by a
+/// variant of the enum. Otherwise the arm could never match,
+/// which is most likely a mistake.
+
+use enum_conversion::prelude::*;
+
+#[EnumConversions]
+enum Enum {
+    F1(i32),
+    F2(bool),
+}
+
+fn main() {
+    let value: Enum = 1.into();
+    let _ = match_variant!(value,
+        i32 => |_| 1,
+        String => |_| 2,
+        _ => 3,
+    );
+}
@@ -0,0 +1,20 @@
+/// An enum with `#[DerivePin]` may not implement `Drop`, as
+/// `drop` takes `&mut self` and could move the values of
+/// pinned variants.
+
+use enum_conversion::prelude::*;
+
+#[EnumConversions]
+#[DerivePin]
+enum Enum {
+    F1(i32),
+    F2(bool),
+}
+
+impl Drop for Enum {
+    fn drop(&mut self) {}
+}
+
+fn main() {
+
+}
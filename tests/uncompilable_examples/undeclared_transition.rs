@@ -0,0 +1,21 @@
+/// Once the transitions of an enum are declared with
+/// `#[Transitions]`, any other transition fails to compile.
+
+use enum_conversion::prelude::*;
+
+struct Connecting;
+struct Open;
+struct Closed;
+
+#[EnumConversions]
+#[Transitions(Connecting => Open, Open => Closed)]
+enum Connection {
+    Connecting(Connecting),
+    Open(Open),
+    Closed(Closed),
+}
+
+fn main() {
+    let mut connection = Connection::Closed(Closed);
+    let _ = connection.transition::<Closed, Open>(|_| Open);
+}